@@ -0,0 +1,59 @@
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::Duration;
+
+use super::protocol::{self, RequestEnum, ResponseEnum};
+use super::AgentError;
+use crate::app::{app::App, credentials_storage};
+
+// The agent answers at once, a slow answer means it is stuck.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sends a request to the running agent. An error response is returned as an error.
+pub fn send(request: &RequestEnum) -> Result<ResponseEnum, AgentError> {
+    let socket_path = super::get_socket_path();
+    let mut stream = UnixStream::connect(&socket_path).map_err(|err| {
+        AgentError(format!(
+            "no agent is listening on {}: {}",
+            socket_path.display(),
+            err
+        ))
+    })?;
+    // The key is only handed to an agent of the same user.
+    super::check_peer(&stream)?;
+    stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+
+    protocol::write_message(&mut stream, request)?;
+    match protocol::read_message(&stream)? {
+        ResponseEnum::Error { message } => Err(AgentError(message)),
+        response => Ok(response),
+    }
+}
+
+pub fn is_running() -> bool {
+    send(&RequestEnum::Status).is_ok()
+}
+
+/// Returns the key the agent holds for the vault, if any.
+pub fn get_key(vault: &Path) -> Option<Vec<u8>> {
+    let response = send(&RequestEnum::GetKey {
+        vault: vault.to_path_buf(),
+    });
+    match response {
+        Ok(ResponseEnum::Key { key }) => key.decode().ok(),
+        _ => None,
+    }
+}
+
+/// Opens the vault in the current directory with the key the agent holds for it. Returns
+/// false when no agent runs, it is locked, or its key does not open the vault.
+pub fn unlock_app(app: &mut App) -> bool {
+    let vault = match credentials_storage::get_vault_path() {
+        Ok(vault) => vault,
+        Err(_) => return false,
+    };
+    match get_key(&vault) {
+        Some(key) => app.load_credentials_with_key(key).is_ok(),
+        None => false,
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::atomic::{compiler_fence, Ordering};
+
+/// A key kept in memory that cannot be swapped out, and that is wiped when dropped.
+pub struct LockedKey {
+    bytes: Box<[u8]>,
+    is_memory_locked: bool,
+}
+
+impl LockedKey {
+    /// Copies the key and wipes the original.
+    pub fn new(key: &mut [u8]) -> Self {
+        let bytes: Box<[u8]> = key.into();
+        wipe(key);
+        // SAFETY: the range is the heap allocation owned by `bytes`, which outlives the lock.
+        let is_memory_locked =
+            unsafe { libc::mlock(bytes.as_ptr() as *const libc::c_void, bytes.len()) } == 0;
+        LockedKey {
+            bytes,
+            is_memory_locked,
+        }
+    }
+
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Whether mlock succeeded. It fails when RLIMIT_MEMLOCK is exhausted.
+    pub fn is_memory_locked(&self) -> bool {
+        self.is_memory_locked
+    }
+}
+
+impl Drop for LockedKey {
+    fn drop(&mut self) {
+        wipe(&mut self.bytes);
+        if self.is_memory_locked {
+            // SAFETY: the same range that was locked in `new`.
+            unsafe {
+                libc::munlock(self.bytes.as_ptr() as *const libc::c_void, self.bytes.len());
+            }
+        }
+    }
+}
+
+/// Overwrites the bytes with zeros in a way the compiler cannot optimize away.
+pub fn wipe(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: `byte` is a valid, aligned reference.
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
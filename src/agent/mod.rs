@@ -0,0 +1,188 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub(super) mod client;
+mod locked_key;
+pub(super) mod protocol;
+pub(super) mod server;
+
+/// Overrides where the agent listens, like SSH_AUTH_SOCK does for ssh-agent.
+pub const SOCKET_ENV_VAR: &str = "KEYLOX_AGENT_SOCK";
+const SOCKET_FILE_NAME: &str = "agent.sock";
+
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+pub const DEFAULT_MAX_LIFETIME: Duration = Duration::from_secs(8 * 60 * 60);
+
+#[derive(Debug)]
+pub struct AgentError(String);
+
+impl fmt::Display for AgentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Agent: {}", self.0)
+    }
+}
+
+impl Error for AgentError {}
+
+impl From<io::Error> for AgentError {
+    fn from(err: io::Error) -> Self {
+        AgentError(err.to_string())
+    }
+}
+
+/// Returns the socket of the agent: KEYLOX_AGENT_SOCK, or a directory of the user in
+/// XDG_RUNTIME_DIR, or in the temporary directory when there is none.
+pub fn get_socket_path() -> PathBuf {
+    if let Some(path) = env::var_os(SOCKET_ENV_VAR) {
+        return PathBuf::from(path);
+    }
+    let directory = match env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("keylox"),
+        None => env::temp_dir().join(format!("keylox-{}", get_uid())),
+    };
+    directory.join(SOCKET_FILE_NAME)
+}
+
+/// Creates the directory of the socket, readable only by the user. An existing directory has
+/// to belong to the user and be closed to others, since the temporary directory is shared.
+fn create_socket_directory(socket_path: &Path) -> Result<(), AgentError> {
+    let directory = match socket_path.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => return Ok(()),
+    };
+    if !directory.exists() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(directory)?;
+        return Ok(());
+    }
+
+    let metadata = std::fs::metadata(directory)?;
+    if metadata.uid() != get_uid() || metadata.permissions().mode() & 0o077 != 0 {
+        return Err(AgentError(format!(
+            "{} has to belong to you and be closed to other users",
+            directory.display()
+        )));
+    }
+    Ok(())
+}
+
+pub fn get_uid() -> u32 {
+    // SAFETY: geteuid cannot fail and has no side effects.
+    unsafe { libc::geteuid() }
+}
+
+/// Returns the user id of the process at the other end of the socket.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn get_peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    let mut credentials = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut length = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    // SAFETY: the buffer and its length describe a valid ucred owned by this function.
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut credentials as *mut libc::ucred as *mut libc::c_void,
+            &mut length,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(credentials.uid)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn get_peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    let mut uid = 0;
+    let mut gid = 0;
+    // SAFETY: both pointers refer to locals that outlive the call.
+    let result = unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(uid)
+}
+
+/// Both ends refuse to talk to a process of another user.
+fn check_peer(stream: &UnixStream) -> Result<(), AgentError> {
+    let peer_uid = get_peer_uid(stream)?;
+    if peer_uid != get_uid() {
+        return Err(AgentError(format!(
+            "refused a connection from user {}",
+            peer_uid
+        )));
+    }
+    Ok(())
+}
+
+/// Parses "90s", "15m", "8h", "1d" or a number of seconds. Zero means no limit.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let (number, unit_seconds) = match input.char_indices().last() {
+        Some((i, 's')) => (&input[..i], 1),
+        Some((i, 'm')) => (&input[..i], 60),
+        Some((i, 'h')) => (&input[..i], 60 * 60),
+        Some((i, 'd')) => (&input[..i], 24 * 60 * 60),
+        _ => (input, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit_seconds))
+        .map(Duration::from_secs)
+        .ok_or_else(|| {
+            format!(
+                "Invalid duration \"{}\", expected a number with s, m, h or d such as 15m",
+                input
+            )
+        })
+}
+
+/// Formats a duration the way `parse_duration` reads it, in its largest whole unit.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds == 0 {
+        return "none".to_string();
+    }
+    for (unit, unit_seconds) in [("d", 24 * 60 * 60), ("h", 60 * 60), ("m", 60)] {
+        if seconds.is_multiple_of(unit_seconds) {
+            return format!("{}{}", seconds / unit_seconds, unit);
+        }
+    }
+    format!("{}s", seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("15m"), Ok(Duration::from_secs(900)));
+        assert_eq!(parse_duration("8h"), Ok(Duration::from_secs(28800)));
+        assert_eq!(parse_duration("2d"), Ok(Duration::from_secs(172800)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration("0"), Ok(Duration::ZERO));
+        assert!(parse_duration("15 minutes").is_err());
+        assert!(parse_duration("").is_err());
+        assert_eq!(format_duration(Duration::from_secs(900)), "15m");
+        assert_eq!(format_duration(Duration::from_secs(90)), "90s");
+        assert_eq!(format_duration(Duration::ZERO), "none");
+    }
+}
@@ -0,0 +1,93 @@
+use data_encoding::{DecodeError, HEXLOWER};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use super::AgentError;
+
+// A request or a response is a single line of JSON. Nothing legitimate comes close to this.
+const MAX_MESSAGE_SIZE: u64 = 64 * 1024;
+
+/// One request per connection. Keys travel hex-encoded.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum RequestEnum {
+    GetKey {
+        vault: PathBuf,
+    },
+    Unlock {
+        vault: PathBuf,
+        key: HexKey,
+    },
+    Lock,
+    Status,
+    // Zero removes a limit, None leaves it as it is.
+    SetTimeouts {
+        idle_timeout_seconds: Option<u64>,
+        max_lifetime_seconds: Option<u64>,
+    },
+    Stop,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+pub enum ResponseEnum {
+    Key { key: HexKey },
+    Status(AgentStatus),
+    Ok,
+    Error { message: String },
+}
+
+/// A hex-encoded key, overwritten once the message that carries it is dropped.
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct HexKey(String);
+
+impl HexKey {
+    pub fn encode(key: &[u8]) -> Self {
+        HexKey(HEXLOWER.encode(key))
+    }
+
+    pub fn decode(&self) -> Result<Vec<u8>, DecodeError> {
+        HEXLOWER.decode(self.0.as_bytes())
+    }
+}
+
+impl Drop for HexKey {
+    fn drop(&mut self) {
+        // SAFETY: zeros are valid UTF-8.
+        super::locked_key::wipe(unsafe { self.0.as_bytes_mut() });
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AgentStatus {
+    pub pid: u32,
+    // The vault whose key is held, None when the agent is locked.
+    pub vault: Option<PathBuf>,
+    pub is_memory_locked: bool,
+    pub idle_timeout_seconds: u64,
+    pub max_lifetime_seconds: u64,
+    // The seconds until the key is dropped because of each limit, while unlocked.
+    pub idle_remaining_seconds: Option<u64>,
+    pub lifetime_remaining_seconds: Option<u64>,
+}
+
+pub fn write_message<T: Serialize>(stream: &mut UnixStream, message: &T) -> Result<(), AgentError> {
+    let mut line = serde_json::to_vec(message).map_err(|err| AgentError(err.to_string()))?;
+    line.push(b'\n');
+    let result = stream.write_all(&line);
+    super::locked_key::wipe(&mut line);
+    Ok(result?)
+}
+
+pub fn read_message<T: DeserializeOwned>(stream: &UnixStream) -> Result<T, AgentError> {
+    let mut reader = BufReader::new(stream.take(MAX_MESSAGE_SIZE));
+    let mut line = Vec::new();
+    reader.read_until(b'\n', &mut line)?;
+    let message = serde_json::from_slice(&line)
+        .map_err(|err| AgentError(format!("invalid message: {}", err)));
+    super::locked_key::wipe(&mut line);
+    message
+}
@@ -0,0 +1,279 @@
+use std::fs;
+use std::io::{self, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::locked_key::LockedKey;
+use super::protocol::{self, AgentStatus, HexKey, RequestEnum, ResponseEnum};
+use super::AgentError;
+
+// How often the timeouts are checked while no request comes in.
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+// A client that does not send its request within this time is dropped.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The unlocked vault.
+struct Session {
+    vault: PathBuf,
+    key: LockedKey,
+    unlocked_at: Instant,
+    last_used_at: Instant,
+}
+
+struct AgentState {
+    session: Option<Session>,
+    idle_timeout: Duration, // zero means no limit.
+    max_lifetime: Duration, // zero means no limit.
+}
+
+impl AgentState {
+    /// Drops the key once it was not used for the idle timeout, or was held for the maximum
+    /// lifetime.
+    fn lock_if_expired(&mut self, now: Instant) {
+        let is_expired = self.session.as_ref().is_some_and(|session| {
+            let is_idle = !self.idle_timeout.is_zero()
+                && now.duration_since(session.last_used_at) >= self.idle_timeout;
+            let is_too_old = !self.max_lifetime.is_zero()
+                && now.duration_since(session.unlocked_at) >= self.max_lifetime;
+            is_idle || is_too_old
+        });
+        if is_expired {
+            self.session = None;
+        }
+    }
+
+    fn get_status(&self, now: Instant) -> AgentStatus {
+        let remaining = |limit: Duration, since: Instant| {
+            (!limit.is_zero()).then(|| limit.saturating_sub(now.duration_since(since)).as_secs())
+        };
+        AgentStatus {
+            pid: std::process::id(),
+            vault: self.session.as_ref().map(|session| session.vault.clone()),
+            is_memory_locked: self
+                .session
+                .as_ref()
+                .is_some_and(|session| session.key.is_memory_locked()),
+            idle_timeout_seconds: self.idle_timeout.as_secs(),
+            max_lifetime_seconds: self.max_lifetime.as_secs(),
+            idle_remaining_seconds: self
+                .session
+                .as_ref()
+                .and_then(|session| remaining(self.idle_timeout, session.last_used_at)),
+            lifetime_remaining_seconds: self
+                .session
+                .as_ref()
+                .and_then(|session| remaining(self.max_lifetime, session.unlocked_at)),
+        }
+    }
+
+    fn handle(&mut self, request: RequestEnum) -> ResponseEnum {
+        let now = Instant::now();
+        self.lock_if_expired(now);
+
+        match request {
+            RequestEnum::GetKey { vault } => match &mut self.session {
+                Some(session) if session.vault == vault => {
+                    session.last_used_at = now;
+                    ResponseEnum::Key {
+                        key: HexKey::encode(session.key.get_bytes()),
+                    }
+                }
+                Some(_) => error("the agent holds the key of another vault"),
+                None => error("the agent is locked"),
+            },
+            RequestEnum::Unlock { vault, key } => match key.decode() {
+                Ok(mut key) => {
+                    self.session = Some(Session {
+                        vault,
+                        key: LockedKey::new(&mut key),
+                        unlocked_at: now,
+                        last_used_at: now,
+                    });
+                    ResponseEnum::Ok
+                }
+                Err(_) => error("the key is not valid hex"),
+            },
+            RequestEnum::Lock => {
+                self.session = None;
+                ResponseEnum::Ok
+            }
+            RequestEnum::Status => ResponseEnum::Status(self.get_status(now)),
+            RequestEnum::SetTimeouts {
+                idle_timeout_seconds,
+                max_lifetime_seconds,
+            } => {
+                if let Some(seconds) = idle_timeout_seconds {
+                    self.idle_timeout = Duration::from_secs(seconds);
+                }
+                if let Some(seconds) = max_lifetime_seconds {
+                    self.max_lifetime = Duration::from_secs(seconds);
+                }
+                self.lock_if_expired(now);
+                ResponseEnum::Status(self.get_status(now))
+            }
+            RequestEnum::Stop => {
+                self.session = None;
+                ResponseEnum::Ok
+            }
+        }
+    }
+}
+
+fn error(message: &str) -> ResponseEnum {
+    ResponseEnum::Error {
+        message: message.to_string(),
+    }
+}
+
+/// Writes to stderr, which may be closed once `agent start` returned.
+fn log(message: &str) {
+    let _ = writeln!(io::stderr(), "{}", message);
+}
+
+/// Keeps the key out of core dumps and, on Linux, away from debuggers of the same user.
+fn harden_process() {
+    let no_core = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: plain system calls on this process with valid arguments.
+    unsafe {
+        libc::setrlimit(libc::RLIMIT_CORE, &no_core);
+        #[cfg(target_os = "linux")]
+        libc::prctl(libc::PR_SET_DUMPABLE, 0, 0, 0, 0);
+    }
+}
+
+/// Binds the socket so that only the user can connect. A socket left behind by an agent that
+/// did not stop cleanly is replaced.
+fn bind(socket_path: &Path) -> Result<UnixListener, AgentError> {
+    super::create_socket_directory(socket_path)?;
+    if socket_path.exists() {
+        if UnixStream::connect(socket_path).is_ok() {
+            return Err(AgentError(format!(
+                "an agent is already listening on {}",
+                socket_path.display()
+            )));
+        }
+        fs::remove_file(socket_path)?;
+    }
+
+    // SAFETY: umask only changes the mode of files created by this process.
+    let previous_umask = unsafe { libc::umask(0o177) };
+    let listener = UnixListener::bind(socket_path);
+    // SAFETY: as above.
+    unsafe { libc::umask(previous_umask) };
+    let listener = listener?;
+    fs::set_permissions(socket_path, fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Serves requests until a stop request comes in.
+pub fn run(
+    socket_path: &Path,
+    idle_timeout: Duration,
+    max_lifetime: Duration,
+) -> Result<(), AgentError> {
+    harden_process();
+    let listener = bind(socket_path)?;
+    let state = Arc::new(Mutex::new(AgentState {
+        session: None,
+        idle_timeout,
+        max_lifetime,
+    }));
+
+    let expiry_state = Arc::clone(&state);
+    thread::spawn(move || loop {
+        thread::sleep(EXPIRY_CHECK_INTERVAL);
+        expiry_state.lock().unwrap().lock_if_expired(Instant::now());
+    });
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                log(&format!("Agent: {}", err));
+                continue;
+            }
+        };
+        if let Err(err) = super::check_peer(&stream) {
+            log(&err.to_string());
+            continue;
+        }
+
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        let request: RequestEnum = match protocol::read_message(&stream) {
+            Ok(request) => request,
+            Err(err) => {
+                let _ = protocol::write_message(&mut stream, &error(&err.to_string()));
+                continue;
+            }
+        };
+        let is_stop = matches!(request, RequestEnum::Stop);
+        let response = state.lock().unwrap().handle(request);
+        if let Err(err) = protocol::write_message(&mut stream, &response) {
+            log(&err.to_string());
+        }
+        if is_stop {
+            break;
+        }
+    }
+
+    fs::remove_file(socket_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unlock(state: &mut AgentState, vault: &str) {
+        let response = state.handle(RequestEnum::Unlock {
+            vault: PathBuf::from(vault),
+            key: HexKey::encode(&[7; 32]),
+        });
+        assert!(matches!(response, ResponseEnum::Ok));
+    }
+
+    fn get_key(state: &mut AgentState, vault: &str) -> Option<Vec<u8>> {
+        match state.handle(RequestEnum::GetKey {
+            vault: PathBuf::from(vault),
+        }) {
+            ResponseEnum::Key { key } => key.decode().ok(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_session() {
+        let mut state = AgentState {
+            session: None,
+            idle_timeout: Duration::from_secs(60),
+            max_lifetime: Duration::from_secs(3600),
+        };
+        assert_eq!(get_key(&mut state, "/vault"), None);
+
+        unlock(&mut state, "/vault");
+        assert_eq!(get_key(&mut state, "/vault"), Some(vec![7; 32]));
+        assert_eq!(get_key(&mut state, "/other"), None);
+
+        let now = Instant::now();
+        state.lock_if_expired(now + Duration::from_secs(30));
+        assert!(state.session.is_some());
+        state.lock_if_expired(now + Duration::from_secs(61));
+        assert!(state.session.is_none());
+
+        unlock(&mut state, "/vault");
+        state.idle_timeout = Duration::ZERO;
+        state.lock_if_expired(Instant::now() + Duration::from_secs(3601));
+        assert!(state.session.is_none());
+
+        unlock(&mut state, "/vault");
+        state.handle(RequestEnum::Lock);
+        assert_eq!(get_key(&mut state, "/vault"), None);
+    }
+}
@@ -1,18 +1,45 @@
 use crate::app::models::{
-    credentials::{Credential, Credentials},
+    attachment::{Attachment, EncryptedAttachment},
+    credentials::{Credential, CredentialSortOrder, Credentials},
+    password_generator_settings::PasswordGeneratorModeEnum,
+    rotation_policy::{RotationPolicyEnum, RotationStatusEnum},
     vault::Vault,
     vault_security_params::{self, Argon2VariantEnum},
+    vault_settings::VaultSettings,
 };
 
 use super::{
-    credentials_storage::{self, load_credentials},
-    models::vault_security_params::{Argon2Params, KdfParamsEnum},
-    pbkdf, vault_encryptor,
+    attachments, breach_check,
+    clipboard::{Clipboard, ClipboardMethodEnum},
+    config::AppConfig,
+    confirmation::{Confirmation, ConfirmationActionEnum},
+    credentials_storage, external_editor,
+    health::HealthReport,
+    history::History,
+    keyring,
+    models::{
+        otp_params::OtpParams,
+        vault_security_params::{Argon2Params, KdfParamsEnum},
+    },
+    otp,
+    otp_import::{self, OtpImportItem},
+    password_generator::{self, GeneratorOptionEnum},
+    password_rules::{self, PasswordRules},
+    password_strength::{self, PasswordStrength},
+    pbkdf,
+    search::{self, SearchResult},
+    text_area::TextArea,
+    text_input::TextInput,
+    vault_encryptor::{self, VaultError},
 };
 
+use chrono::{DateTime, Utc};
 use rand::rngs::OsRng;
 use rand::Rng;
+use std::collections::HashMap;
 use std::error::Error;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 pub enum CurrentScreen {
     Init,
@@ -21,7 +48,13 @@ pub enum CurrentScreen {
     MainCredentialScreen,
     WebsiteCredentialScreen,
     SpecificCredentialScreen,
-    Exiting,
+    OtpImportScreen,
+    AttachmentPathScreen,
+    PasswordGeneratorScreen,
+    TrashScreen,
+    RotationScreen,
+    HealthScreen,
+    SearchScreen,
 }
 
 pub enum CurrentlyEditingCredentialField {
@@ -29,82 +62,322 @@ pub enum CurrentlyEditingCredentialField {
     Email,
     Username,
     Password,
+    PasswordRules,
+    Otp,
+    Attachments,
+    Tags,
+    Rotation,
     Notes,
 }
 
+pub enum AttachmentActionEnum {
+    Add,
+    Export,
+}
+
+#[derive(Clone, Copy)]
+pub enum CopyFieldEnum {
+    Username,
+    Password,
+    OtpCode,
+}
+
 pub struct App {
     pub unsaved_changes: bool, // a flag to determine if there are unsaved changes.
-    pub websites: Vec<String>, // the list of credentials that the user has saved.
-    pub selected_website_index: usize, // the currently selected credential.
-    pub emails: Vec<String>,   // the list of emails that the user has saved.
-    pub selected_email_index: usize, // the currently selected email.
+    pub saved_credentials: Option<Credentials>, // the credentials as last loaded or saved, None while the vault is not stored yet.
+    pub websites: Vec<String>,                  // the list of credentials that the user has saved.
+    pub website_timestamps: Vec<Option<DateTime<Utc>>>, // the newest timestamp of each website for the sort order.
+    pub website_rotation_statuses: Vec<RotationStatusEnum>, // the most urgent rotation status of each website.
+    pub selected_website_index: usize,                      // the currently selected credential.
+    pub sort_order: CredentialSortOrder, // the order in which the websites are listed.
+    pub selected_trash_index: usize,     // the currently selected credential in the trash.
+    pub selected_rotation_index: usize,  // the currently selected credential due for rotation.
+    pub health_report: Option<HealthReport>, // the last analysis shown on the health screen.
+    pub breach_counts: HashMap<(String, String), u64>, // breach prevalence by website and email.
+    pub selected_health_index: usize,    // the currently selected finding of the health report.
+    pub search_input: TextInput,         // the query of the search across the vault.
+    pub search_results: Vec<SearchResult>, // the credentials matching the query, best first.
+    pub selected_search_index: usize,    // the currently selected search result.
+    pub rotation_defaults_input: Option<TextInput>, // the "tag=days" defaults being edited, if any.
+    pub emails: Vec<String>,             // the list of emails that the user has saved.
+    pub selected_email_index: usize,     // the currently selected email.
     pub currently_editing_credential_field: Option<CurrentlyEditingCredentialField>, // the optional state containing which of the username or password the user is editing. It is an option, because when the user is not directly editing a credential, this will be set to `None`.
 
     pub master_key: Vec<u8>,
     pub kdf_params: Option<KdfParamsEnum>,
     pub credentials: Credentials,
+    pub history: History, // the operations on the credentials that can be undone.
 
-    pub new_password_input: String, // the new password that the user is trying to set.
-    pub master_password_input: String, // the currently being edited master password.
+    pub new_password_input: TextInput, // the new password that the user is trying to set.
+    pub master_password_input: TextInput, // the currently being edited master password.
 
-    pub website_input: String,
-    pub email_input: String,
-    pub username_input: String,
-    pub password_input: String,
-    pub notes_input: String,
+    pub website_input: TextInput,
+    pub email_input: TextInput,
+    pub username_input: TextInput,
+    pub password_input: TextInput,
+    pub notes_input: TextArea,
+    pub otp_input: TextInput, // a base32 secret or an otpauth:// URI.
+    pub attachments_input: Vec<Attachment>, // the attachments of the credential being edited.
+    pub tags_input: TextInput, // comma-separated tags.
+    pub password_rules_input: TextInput, // the site's rules in Apple's passwordrules syntax.
+    pub rotation_input: TextInput, // a rotation interval such as "90d" or an expiry date.
+    pub selected_attachment_index: usize,
+    pub attachment_path_input: TextInput, // the file to attach or to export to.
+    pub attachment_action: Option<AttachmentActionEnum>,
+    pub selected_generator_option: usize, // the option selected in the password generator.
+    pub generated_password: String,       // the last password shown by the generator.
+    pub is_generating_master_password: bool, // whether the generator was opened for a new master password.
+    pub attachment_store: HashMap<String, EncryptedAttachment>, // the encrypted content of every attachment, by id.
+    pub loaded_credential: Option<Credential>, // the credential as it was when opened in the editor.
+    pub otp_import_input: TextInput, // a pasted otpauth URI or the path to a file of URIs.
+    pub otp_import_items: Vec<OtpImportItem>, // the accounts previewed before merging.
+    pub selected_otp_import_index: usize,
+    pub status_message: Option<String>, // a message shown in the footer until the next key press.
+    pub confirmation: Option<Confirmation>, // the question shown over the screen, if any.
+    pub config: AppConfig,              // settings read from config.json.
+    pub clipboard: Clipboard,           // the clipboard, and the copied value to clear in time.
+    pub secrets_revealed_at: Option<Instant>, // when the masked fields were revealed, if they are.
+    pub is_external_editor_requested: bool, // whether the notes are to be opened in $EDITOR.
     pub current_screen: CurrentScreen, // the current screen the user is looking at, and will later determine what is rendered.
     pub currently_editing: Option<CurrentlyEditingCredentialField>, // the optional state containing which of the key or value pair the user is editing. It is an option, because when the user is not directly editing a key-value pair, this will be set to `None`.
 }
 
 impl App {
     pub fn new() -> App {
+        let (config, status_message) = match AppConfig::load() {
+            Ok(config) => (config, None),
+            Err(err) => (
+                AppConfig::default(),
+                Some(format!(
+                    "Could not read config.json, using the defaults: {}",
+                    err
+                )),
+            ),
+        };
+
         let app = App {
-            unsaved_changes: true,
+            unsaved_changes: false,
+            saved_credentials: None,
             websites: Vec::new(),
+            website_timestamps: Vec::new(),
+            website_rotation_statuses: Vec::new(),
             selected_website_index: 0,
+            sort_order: CredentialSortOrder::Website,
+            selected_trash_index: 0,
+            selected_rotation_index: 0,
+            health_report: None,
+            breach_counts: HashMap::new(),
+            selected_health_index: 0,
+            search_input: TextInput::new(),
+            search_results: Vec::new(),
+            selected_search_index: 0,
+            rotation_defaults_input: None,
             emails: Vec::new(),
             selected_email_index: 0,
             currently_editing_credential_field: None,
 
-            new_password_input: String::new(),
-            master_password_input: String::new(),
+            new_password_input: TextInput::new(),
+            master_password_input: TextInput::new(),
 
-            website_input: String::new(),
-            email_input: String::new(),
-            username_input: String::new(),
-            password_input: String::new(),
-            notes_input: String::new(),
+            website_input: TextInput::new(),
+            email_input: TextInput::new(),
+            username_input: TextInput::new(),
+            password_input: TextInput::new(),
+            notes_input: TextArea::new(),
+            otp_input: TextInput::new(),
+            attachments_input: Vec::new(),
+            tags_input: TextInput::new(),
+            password_rules_input: TextInput::new(),
+            rotation_input: TextInput::new(),
+            selected_attachment_index: 0,
+            attachment_path_input: TextInput::new(),
+            attachment_action: None,
+            selected_generator_option: 0,
+            generated_password: String::new(),
+            is_generating_master_password: false,
+            attachment_store: HashMap::new(),
+            loaded_credential: None,
+            otp_import_input: TextInput::new(),
+            otp_import_items: Vec::new(),
+            selected_otp_import_index: 0,
+            status_message,
+            confirmation: None,
+            config,
+            clipboard: Clipboard::new(),
+            secrets_revealed_at: None,
+            is_external_editor_requested: false,
             current_screen: CurrentScreen::Init,
             currently_editing: None,
 
             credentials: Credentials::new(),
+            history: History::new(),
             master_key: Vec::new(),
             kdf_params: None,
         };
 
-        load_credentials().unwrap();
-
         return app;
     }
 
     pub fn load_credentials(&mut self, password: &str) -> Result<(), Box<dyn Error>> {
-        if let Some(vault) =
-            credentials_storage::load_credentials().expect("Failed to load credentials.")
+        // TODO: Error handling
+        self.load_vault(|kdf_params| pbkdf::derive_key(kdf_params, password).unwrap())
+    }
+
+    /// Opens the vault with a key derived earlier, such as the one the agent holds.
+    pub fn load_credentials_with_key(&mut self, key: Vec<u8>) -> Result<(), Box<dyn Error>> {
+        self.load_vault(|_| key)
+    }
+
+    /// Opens the vault with the key an earlier unlock cached in the kernel keyring, when
+    /// config.json enables the cache. Returns whether it worked.
+    pub fn load_credentials_from_keyring(&mut self) -> bool {
+        if self.config.keyring_timeout_minutes == 0 {
+            return false;
+        }
+        let key = Self::get_stored_kdf_params()
+            .and_then(|kdf_params| keyring::get_vault_key_description(&kdf_params))
+            .and_then(|description| keyring::get_key(&description).ok().flatten());
+        match key {
+            Some(key) => self.load_credentials_with_key(key).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Caches the key of the opened vault in the kernel keyring, when config.json enables it.
+    pub fn cache_master_key(&mut self) {
+        if self.config.keyring_timeout_minutes == 0 {
+            return;
+        }
+        let description = match self
+            .kdf_params
+            .as_ref()
+            .and_then(keyring::get_vault_key_description)
+        {
+            Some(description) => description,
+            None => return,
+        };
+        let timeout_seconds = self.config.keyring_timeout_minutes.saturating_mul(60);
+        if let Err(err) = keyring::store_key(&description, &self.master_key, timeout_seconds) {
+            self.status_message = Some(err.to_string());
+        }
+    }
+
+    /// Revokes the key of the vault in the kernel keyring. Returns whether there was one.
+    pub fn revoke_cached_master_key() -> Result<bool, Box<dyn Error>> {
+        let description = Self::get_stored_kdf_params()
+            .and_then(|kdf_params| keyring::get_vault_key_description(&kdf_params));
+        match description {
+            Some(description) => Ok(keyring::revoke_key(&description)?),
+            None => Ok(false),
+        }
+    }
+
+    /// Reads the key derivation parameters of the vault, which are not encrypted.
+    fn get_stored_kdf_params() -> Option<KdfParamsEnum> {
+        let vault = credentials_storage::load_credentials().ok().flatten()?;
+        Some(vault.vault_security_params.get_kdf_params())
+    }
+
+    fn load_vault(
+        &mut self,
+        get_key: impl FnOnce(&KdfParamsEnum) -> Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(vault) = credentials_storage::load_credentials()
+            .map_err(|err| VaultError::Corrupted(err.to_string()))?
         {
-            // TODO: Error handling
             self.kdf_params = Some(vault.vault_security_params.get_kdf_params().clone());
+            self.master_key = get_key(self.kdf_params.as_ref().unwrap());
 
-            self.master_key =
-                pbkdf::derive_key(self.kdf_params.as_ref().unwrap(), &password).unwrap();
+            self.attachment_store = vault
+                .attachments
+                .iter()
+                .map(|attachment| (attachment.get_id(), attachment.clone()))
+                .collect();
 
             self.credentials = vault_encryptor::decrypt(&self.master_key.to_vec(), vault)?;
+
+            let purged = self.credentials.empty_expired_trash();
+            if purged > 0 {
+                self.status_message = Some(format!(
+                    "Purged {} expired credential(s) from the trash",
+                    purged
+                ));
+            }
+            // The purge happens again on the next load, so it does not count as a change.
+            self.saved_credentials = Some(self.credentials.clone());
         }
 
-        self.websites = self.credentials.get_websites();
+        self.refresh_websites();
+        self.refresh_breach_counts();
 
         Ok(())
     }
 
+    /// Checks every password against the configured breach list. Only the prevalence of the
+    /// breached entries is kept.
+    pub fn refresh_breach_counts(&mut self) {
+        self.breach_counts.clear();
+        if self.config.breach_list_path.is_empty() {
+            return;
+        }
+        match breach_check::get_breach_counts(
+            &self.credentials,
+            Path::new(&self.config.breach_list_path),
+        ) {
+            Ok(breach_counts) => self.breach_counts = breach_counts,
+            Err(err) => self.status_message = Some(err.to_string()),
+        }
+    }
+
+    pub fn get_breach_count(&self, website: &str, email: &str) -> Option<u64> {
+        self.breach_counts
+            .get(&(website.to_string(), email.to_string()))
+            .copied()
+    }
+
+    pub fn is_website_breached(&self, website: &str) -> bool {
+        self.breach_counts.keys().any(|(w, _)| w == website)
+    }
+
+    pub fn refresh_websites(&mut self) {
+        (self.websites, self.website_timestamps) = self
+            .credentials
+            .get_websites_sorted(self.sort_order)
+            .into_iter()
+            .unzip();
+        let statuses = self.credentials.get_website_rotation_statuses();
+        self.website_rotation_statuses = self
+            .websites
+            .iter()
+            .map(|website| statuses[website.as_str()])
+            .collect();
+    }
+
+    /// Records that the credential was viewed or copied. The list keeps its order until it is
+    /// refreshed, but shows the new access time right away.
+    fn mark_accessed(&mut self, website: &str, email: &str) {
+        self.credentials.mark_accessed(website, email);
+        if self.sort_order == CredentialSortOrder::LastAccessed {
+            if let Some(i) = self.websites.iter().position(|w| w == website) {
+                self.website_timestamps[i] = Some(Utc::now());
+            }
+        }
+    }
+
+    pub fn cycle_sort_order(&mut self) {
+        let selected_website = self.websites.get(self.selected_website_index).cloned();
+
+        self.sort_order = self.sort_order.next();
+        self.refresh_websites();
+
+        // Keep the same website selected after reordering.
+        if let Some(website) = selected_website {
+            if let Some(index) = self.websites.iter().position(|w| *w == website) {
+                self.selected_website_index = index;
+            }
+        }
+    }
+
     pub fn load_emails(&mut self) {
         // TODO: refactor
         if self.websites.len() == 0 {
@@ -135,15 +408,30 @@ impl App {
             // todo: log
             return;
         }
-        let website = &self.websites[self.selected_website_index];
-        let email = &self.emails[self.selected_email_index];
+        let website = self.websites[self.selected_website_index].clone();
+        let email = self.emails[self.selected_email_index].clone();
 
-        if let Some(credential) = self.credentials.get_credential(website, email) {
-            self.website_input = credential.website.clone();
-            self.email_input = credential.email.clone();
-            self.username_input = credential.username.clone();
-            self.password_input = credential.password.clone();
-            self.notes_input = credential.notes.clone();
+        self.mark_accessed(&website, &email);
+        if let Some(credential) = self.credentials.get_credential(&website, &email) {
+            self.website_input.set_value(&credential.website);
+            self.email_input.set_value(&credential.email);
+            self.username_input.set_value(&credential.username);
+            self.password_input.set_value(&credential.password);
+            self.notes_input.set_value(&credential.notes);
+            self.otp_input.set_value(&match &credential.otp {
+                Some(otp_params) => otp::to_uri(otp_params),
+                None => String::new(),
+            });
+            self.attachments_input = credential.attachments.clone();
+            self.selected_attachment_index = 0;
+            self.tags_input.set_value(&credential.tags.join(", "));
+            self.password_rules_input
+                .set_value(&credential.password_rules);
+            self.rotation_input.set_value(&match &credential.rotation {
+                Some(rotation) => rotation.to_input_string(),
+                None => String::new(),
+            });
+            self.loaded_credential = Some(credential);
         }
     }
 
@@ -159,7 +447,15 @@ impl App {
         self.username_input.clear();
         self.password_input.clear();
         self.notes_input.clear();
+        self.otp_input.clear();
+        self.attachments_input.clear();
+        self.selected_attachment_index = 0;
+        self.tags_input.clear();
+        self.password_rules_input.clear();
+        self.rotation_input.clear();
+        self.loaded_credential = None;
         self.currently_editing = None;
+        self.hide_secrets();
     }
 
     pub fn remove_selected_credential(&mut self) {
@@ -182,31 +478,954 @@ impl App {
         let website = &self.websites[self.selected_website_index];
         let email = &self.emails[self.selected_email_index];
 
-        self.credentials.remove_credential(website, email);
-        self.websites = self.credentials.get_websites();
+        let previous = self.credentials.clone();
+        if self.credentials.move_to_trash(website, email) {
+            self.history.record(
+                format!("move {} / {} to the trash", website, email),
+                previous,
+            );
+            self.status_message = Some(format!("Moved {} / {} to the trash", website, email));
+        }
+        self.refresh_websites();
         self.load_emails();
         self.discard_unsaved_credentials();
     }
 
-    pub fn save_credential(&mut self) {
-        let credential = Credential::new(
-            Some(self.website_input.clone()),
-            Some(self.email_input.clone()),
-            Some(self.username_input.clone()),
-            Some(self.password_input.clone()),
-            Some(self.notes_input.clone()),
+    pub fn restore_selected_trash_credential(&mut self) {
+        let previous = self.credentials.clone();
+        match self
+            .credentials
+            .restore_from_trash(self.selected_trash_index)
+        {
+            Ok(_) => {
+                self.history
+                    .record("restore from the trash".to_string(), previous);
+                self.status_message = Some("Credential restored".to_string());
+                self.refresh_websites();
+                self.clamp_selected_trash_index();
+            }
+            Err(err) => {
+                self.status_message = Some(err);
+            }
+        }
+    }
+
+    pub fn purge_selected_trash_credential(&mut self) {
+        let previous = self.credentials.clone();
+        if let Some(trashed) = self.credentials.purge_from_trash(self.selected_trash_index) {
+            self.history.record(
+                format!(
+                    "permanent deletion of {} / {}",
+                    trashed.credential.website, trashed.credential.email
+                ),
+                previous,
+            );
+            self.status_message = Some(format!(
+                "Permanently deleted {} / {}",
+                trashed.credential.website, trashed.credential.email
+            ));
+            self.clamp_selected_trash_index();
+        }
+    }
+
+    pub fn change_trash_retention(&mut self, days: i64) {
+        let retention = self.credentials.settings.trash_retention_days as i64 + days;
+        self.history.record(
+            "trash retention change".to_string(),
+            self.credentials.clone(),
+        );
+        self.credentials.settings.trash_retention_days = retention.clamp(0, 3650) as u32;
+    }
+
+    fn clamp_selected_trash_index(&mut self) {
+        self.selected_trash_index = std::cmp::min(
+            self.selected_trash_index,
+            self.credentials.trash.len().saturating_sub(1),
+        );
+    }
+
+    pub fn save_credential(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut credential = Credential::new(
+            Some(self.website_input.get_value().to_string()),
+            Some(self.email_input.get_value().to_string()),
+            Some(self.username_input.get_value().to_string()),
+            Some(self.password_input.get_value().to_string()),
+            Some(self.notes_input.get_value().to_string()),
         );
+        if !self.otp_input.get_value().trim().is_empty() {
+            credential.otp = Some(otp::parse_otp(self.otp_input.get_value())?);
+        }
+        credential.attachments = self.attachments_input.clone();
+        credential.tags = self
+            .tags_input
+            .get_value()
+            .split(',')
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        credential.rotation = RotationPolicyEnum::parse(self.rotation_input.get_value())?;
+        if !self.password_rules_input.get_value().trim().is_empty() {
+            PasswordRules::parse(self.password_rules_input.get_value())?;
+        }
+        credential.password_rules = self.password_rules_input.get_value().trim().to_string();
 
+        let action = match self
+            .credentials
+            .get_credential(&credential.website, &credential.email)
+        {
+            Some(_) => "edit",
+            None => "creation",
+        };
+        self.history.record(
+            format!(
+                "{} of {} / {}",
+                action, credential.website, credential.email
+            ),
+            self.credentials.clone(),
+        );
         self.credentials.add_or_update_credential(credential);
-        self.websites = self.credentials.get_websites();
+        self.refresh_websites();
+        self.refresh_breach_counts();
 
         self.discard_unsaved_credentials();
+        Ok(())
+    }
+
+    /// Returns whether the editor holds changes that leaving it would lose. A new credential
+    /// counts as changed once anything besides its website, which may be filled in from the
+    /// list, is entered.
+    pub fn is_editing_credential_modified(&self) -> bool {
+        let stored = self.loaded_credential.as_ref().and_then(|loaded| {
+            self.credentials
+                .get_credential(&loaded.website, &loaded.email)
+        });
+        let values = self.get_editor_values();
+        match stored {
+            Some(credential) => {
+                values != get_editor_values(&credential)
+                    || self.attachments_input != credential.attachments
+            }
+            None => {
+                values[1..].iter().any(|value| !value.trim().is_empty())
+                    || !self.attachments_input.is_empty()
+            }
+        }
+    }
+
+    /// Returns whether saving would replace another credential than the one being edited, as
+    /// after changing the website or email to those of an existing entry.
+    pub fn is_overwriting_other_credential(&self) -> bool {
+        let website = self.website_input.get_value();
+        let email = self.email_input.get_value();
+        let is_loaded = self
+            .loaded_credential
+            .as_ref()
+            .is_some_and(|loaded| loaded.website == website && loaded.email == email);
+        !is_loaded && self.credentials.get_credential(website, email).is_some()
+    }
+
+    /// The text of the editor fields, from the website to the rotation.
+    fn get_editor_values(&self) -> Vec<String> {
+        vec![
+            self.website_input.get_value().to_string(),
+            self.email_input.get_value().to_string(),
+            self.username_input.get_value().to_string(),
+            self.password_input.get_value().to_string(),
+            self.notes_input.get_value().to_string(),
+            self.otp_input.get_value().to_string(),
+            self.tags_input.get_value().to_string(),
+            self.password_rules_input.get_value().to_string(),
+            self.rotation_input.get_value().to_string(),
+        ]
+    }
+
+    pub fn ask_to_move_selected_credential_to_trash(&mut self) {
+        let (website, email) = match (
+            self.websites.get(self.selected_website_index),
+            self.emails.get(self.selected_email_index),
+        ) {
+            (Some(website), Some(email)) => (website, email),
+            _ => return,
+        };
+        self.confirmation = Some(Confirmation::new(
+            "Move to the trash",
+            format!("Move {} / {} to the trash?", website, email),
+            "Move to the trash",
+            ConfirmationActionEnum::MoveToTrash,
+        ));
+    }
+
+    pub fn ask_to_purge_selected_trash_credential(&mut self) {
+        let credential = match self.credentials.trash.get(self.selected_trash_index) {
+            Some(trashed) => &trashed.credential,
+            None => return,
+        };
+        self.confirmation = Some(Confirmation::new(
+            "Delete permanently",
+            format!(
+                "Permanently delete {} / {}? It cannot be restored from the trash.",
+                credential.website, credential.email
+            ),
+            "Delete permanently",
+            ConfirmationActionEnum::PurgeFromTrash,
+        ));
+    }
+
+    pub fn ask_to_remove_selected_attachment(&mut self) {
+        let attachment = match self.attachments_input.get(self.selected_attachment_index) {
+            Some(attachment) => attachment,
+            None => return,
+        };
+        self.confirmation = Some(Confirmation::new(
+            "Remove attachment",
+            format!(
+                "Remove {} from this credential?",
+                attachment.get_file_name()
+            ),
+            "Remove",
+            ConfirmationActionEnum::RemoveAttachment,
+        ));
+    }
+
+    pub fn ask_to_discard_changes(&mut self) {
+        let message = match &self.loaded_credential {
+            Some(credential) => format!(
+                "Discard the changes to {} / {}?",
+                credential.website, credential.email
+            ),
+            None => "Discard the new credential?".to_string(),
+        };
+        self.confirmation = Some(Confirmation::new(
+            "Unsaved changes",
+            message,
+            "Discard",
+            ConfirmationActionEnum::DiscardChanges,
+        ));
+    }
+
+    pub fn ask_to_overwrite_credential(&mut self) {
+        self.confirmation = Some(Confirmation::new(
+            "Replace credential",
+            format!(
+                "{} / {} already exists. Replace it with this credential?",
+                self.website_input.get_value(),
+                self.email_input.get_value()
+            ),
+            "Replace",
+            ConfirmationActionEnum::OverwriteCredential,
+        ));
+    }
+
+    pub fn ask_to_merge_otp_import(&mut self) {
+        let accepted: Vec<&OtpImportItem> = self
+            .otp_import_items
+            .iter()
+            .filter(|item| item.accepted)
+            .collect();
+        if accepted.is_empty() {
+            self.status_message = Some("No account is accepted, (Space) accepts one".to_string());
+            return;
+        }
+
+        let replaced = accepted
+            .iter()
+            .filter(|item| {
+                item.is_existing
+                    && self
+                        .credentials
+                        .get_credential(&item.website, &item.email)
+                        .is_some_and(|credential| credential.otp.is_some())
+            })
+            .count();
+        let mut message = format!("Import {} one-time password(s)?", accepted.len());
+        if replaced > 0 {
+            message.push_str(&format!(
+                " {} of them replace the one-time password of an existing credential.",
+                replaced
+            ));
+        }
+        self.confirmation = Some(Confirmation::new(
+            "Import one-time passwords",
+            message,
+            "Import",
+            ConfirmationActionEnum::MergeOtpImport,
+        ));
+    }
+
+    pub fn ask_to_exit(&mut self) {
+        let mut message = self.get_unsaved_changes_summary();
+        message.push(String::new());
+        message.push("Would you like to save your changes?".to_string());
+        self.confirmation = Some(Confirmation::with_choices(
+            "Unsaved changes",
+            message.join("\n"),
+            vec![
+                ('y', "Save and quit", ConfirmationActionEnum::SaveAndQuit),
+                (
+                    'n',
+                    "Quit without saving",
+                    ConfirmationActionEnum::QuitWithoutSaving,
+                ),
+            ],
+        ));
+    }
+
+    /// Opens the editor on the credential selected in the rotation list.
+    pub fn open_selected_due_credential(&mut self) {
+        let due = self.credentials.get_due_for_rotation();
+        if let Some((credential, _)) = due.get(self.selected_rotation_index) {
+            self.open_credential(&credential.website, &credential.email);
+        }
+    }
+
+    /// Opens a credential in the editor with the password field selected.
+    fn open_credential(&mut self, website: &str, email: &str) {
+        if !self.select_credential(website, email) {
+            return;
+        }
+
+        self.load_credential();
+        self.current_screen = CurrentScreen::SpecificCredentialScreen;
+        self.currently_editing_credential_field = Some(CurrentlyEditingCredentialField::Password);
+    }
+
+    /// Selects a credential in the website and email lists. Returns false if it does not exist.
+    pub fn select_credential(&mut self, website: &str, email: &str) -> bool {
+        self.refresh_websites();
+        self.selected_website_index = match self.websites.iter().position(|w| w == website) {
+            Some(index) => index,
+            None => return false,
+        };
+        self.load_emails();
+        self.selected_email_index = match self.emails.iter().position(|e| e == email) {
+            Some(index) => index,
+            None => return false,
+        };
+        true
+    }
+
+    /// Analyzes the vault again and shows the health screen.
+    pub fn open_health_report(&mut self) {
+        let report = HealthReport::analyze(&self.credentials, &self.breach_counts, Utc::now());
+        self.selected_health_index = std::cmp::min(
+            self.selected_health_index,
+            report.findings.len().saturating_sub(1),
+        );
+        self.health_report = Some(report);
+        self.current_screen = CurrentScreen::HealthScreen;
+    }
+
+    pub fn open_selected_health_finding(&mut self) {
+        let finding = match &self.health_report {
+            Some(report) => match report.findings.get(self.selected_health_index) {
+                Some(finding) => finding.clone(),
+                None => return,
+            },
+            None => return,
+        };
+        self.open_credential(&finding.website, &finding.email);
+    }
+
+    /// Shows the search screen with an empty query, which lists every credential.
+    pub fn open_search(&mut self) {
+        self.search_input.clear();
+        let credentials = &self.credentials.credentials;
+        self.search_results = search::search(credentials, "", 0..credentials.len());
+        self.selected_search_index = 0;
+        self.current_screen = CurrentScreen::SearchScreen;
+    }
+
+    /// Searches again after the query changed from `previous_query`.
+    pub fn update_search_results(&mut self, previous_query: &str) {
+        let query = self.search_input.get_value().trim();
+        let previous_query = previous_query.trim();
+        if query == previous_query {
+            return;
+        }
+
+        let credentials = &self.credentials.credentials;
+        // A longer query only matches what the shorter one matched, so as the user types
+        // only the previous results are searched again.
+        self.search_results = if !previous_query.is_empty() && query.starts_with(previous_query) {
+            let indices: Vec<usize> = self.search_results.iter().map(|r| r.index).collect();
+            search::search(credentials, query, indices)
+        } else {
+            search::search(credentials, query, 0..credentials.len())
+        };
+        self.selected_search_index = 0;
+    }
+
+    pub fn open_selected_search_result(&mut self) {
+        let credential = match self.search_results.get(self.selected_search_index) {
+            Some(result) => &self.credentials.credentials[result.index],
+            None => return,
+        };
+        let (website, email) = (credential.website.clone(), credential.email.clone());
+        self.open_credential(&website, &email);
+    }
+
+    pub fn change_password_max_age(&mut self, days: i64) {
+        let max_age = self.credentials.settings.password_max_age_days as i64 + days;
+        self.history.record(
+            "password age limit change".to_string(),
+            self.credentials.clone(),
+        );
+        self.credentials.settings.password_max_age_days = max_age.clamp(0, 3650) as u32;
+        self.open_health_report();
+    }
+
+    pub fn edit_rotation_defaults(&mut self) {
+        self.rotation_defaults_input = Some(TextInput::from(
+            self.credentials
+                .settings
+                .format_rotation_defaults()
+                .as_str(),
+        ));
+    }
+
+    pub fn apply_rotation_defaults(&mut self) -> Result<(), String> {
+        let input = match &self.rotation_defaults_input {
+            Some(input) => input,
+            None => return Ok(()),
+        };
+
+        let rotation_defaults = VaultSettings::parse_rotation_defaults(input.get_value())?;
+        if rotation_defaults != self.credentials.settings.rotation_defaults {
+            self.history.record(
+                "rotation defaults change".to_string(),
+                self.credentials.clone(),
+            );
+            self.credentials.settings.rotation_defaults = rotation_defaults;
+            self.refresh_websites();
+        }
+        self.rotation_defaults_input = None;
+        self.selected_rotation_index = 0;
+        Ok(())
+    }
+
+    /// Returns the OTP parameters currently entered in the editor, if they are valid.
+    pub fn get_editing_otp(&self) -> Option<OtpParams> {
+        if self.otp_input.get_value().trim().is_empty() {
+            return None;
+        }
+        otp::parse_otp(self.otp_input.get_value()).ok()
+    }
+
+    /// Advances the HOTP counter so the next code is shown. The stored credential is
+    /// updated right away so a used code is never shown again.
+    pub fn next_hotp_code(&mut self) {
+        let mut otp_params = match self.get_editing_otp() {
+            Some(otp_params) if otp_params.is_counter_based() => otp_params,
+            _ => return,
+        };
+
+        otp_params.increment_counter();
+        self.otp_input.set_value(&otp::to_uri(&otp_params));
+
+        if let Some(credential) = &self.loaded_credential {
+            self.credentials
+                .set_otp(&credential.website, &credential.email, Some(otp_params));
+        }
+    }
+
+    /// Copies a field of the credential being edited, or of the one selected in the list of
+    /// emails, which then counts as accessed.
+    pub fn copy_credential_field(&mut self, field: CopyFieldEnum) {
+        let is_editing = matches!(self.current_screen, CurrentScreen::SpecificCredentialScreen);
+        let (website, username, password, otp_params) = if is_editing {
+            (
+                self.website_input.get_value().to_string(),
+                self.username_input.get_value().to_string(),
+                self.password_input.get_value().to_string(),
+                self.get_editing_otp(),
+            )
+        } else {
+            let credential = match (
+                self.websites.get(self.selected_website_index),
+                self.emails.get(self.selected_email_index),
+            ) {
+                (Some(website), Some(email)) => self.credentials.get_credential(website, email),
+                _ => None,
+            };
+            let credential = match credential {
+                Some(credential) => credential,
+                None => return,
+            };
+            self.mark_accessed(&credential.website, &credential.email);
+            (
+                credential.website,
+                credential.username,
+                credential.password,
+                credential.otp,
+            )
+        };
+
+        let (name, value) = match field {
+            CopyFieldEnum::Username => ("username", username),
+            CopyFieldEnum::Password => ("password", password),
+            CopyFieldEnum::OtpCode => match otp_params {
+                Some(otp_params) => (
+                    "OTP code",
+                    otp::generate_code(&otp_params, otp::get_unix_time()),
+                ),
+                None => ("OTP code", String::new()),
+            },
+        };
+        if value.is_empty() {
+            self.status_message = Some(format!("{} has no {}", website, name));
+            return;
+        }
+
+        let clear_after = Duration::from_secs(self.config.clipboard_clear_seconds as u64);
+        self.status_message = Some(match self.clipboard.copy(&value, clear_after) {
+            Ok(method) => {
+                let via = match method {
+                    ClipboardMethodEnum::System => "",
+                    ClipboardMethodEnum::Osc52 => " through the terminal",
+                };
+                if clear_after.is_zero() {
+                    format!("Copied the {} of {}{}", name, website, via)
+                } else {
+                    format!(
+                        "Copied the {} of {}{}, clearing it in {}s",
+                        name,
+                        website,
+                        via,
+                        clear_after.as_secs()
+                    )
+                }
+            }
+            Err(err) => err.to_string(),
+        });
+    }
+
+    /// Returns whether passwords and other secrets are shown in clear text. A reveal with a
+    /// configured duration ends on its own.
+    pub fn is_secret_revealed(&self) -> bool {
+        let reveal_duration = Duration::from_secs(self.config.password_reveal_seconds as u64);
+        self.secrets_revealed_at.is_some_and(|revealed_at| {
+            reveal_duration.is_zero() || revealed_at.elapsed() < reveal_duration
+        })
+    }
+
+    /// Reveals the masked fields, or masks them again.
+    pub fn toggle_secrets_revealed(&mut self) {
+        if self.is_secret_revealed() {
+            self.hide_secrets();
+            return;
+        }
+
+        self.secrets_revealed_at = Some(Instant::now());
+        if self.config.password_reveal_seconds > 0 {
+            self.status_message = Some(format!(
+                "Revealed for {}s",
+                self.config.password_reveal_seconds
+            ));
+        }
+    }
+
+    pub fn hide_secrets(&mut self) {
+        self.secrets_revealed_at = None;
+    }
+
+    /// Inserts pasted text in the focused input.
+    pub fn paste_text(&mut self, text: &str) {
+        if self.confirmation.is_some() {
+            return;
+        }
+
+        if matches!(self.current_screen, CurrentScreen::SpecificCredentialScreen)
+            && matches!(
+                self.currently_editing_credential_field,
+                Some(CurrentlyEditingCredentialField::Notes)
+            )
+        {
+            self.notes_input.insert_str(text);
+        } else if matches!(self.current_screen, CurrentScreen::SearchScreen) {
+            let previous_query = self.search_input.get_value().to_string();
+            self.search_input.insert_str(text);
+            self.update_search_results(&previous_query);
+        } else if let Some(input) = self.get_focused_text_input() {
+            input.insert_str(text);
+        }
+    }
+
+    /// Hands the notes to the editor of the user. The terminal has to be handed over to the
+    /// editor before.
+    pub fn edit_notes_in_external_editor(&mut self) {
+        self.is_external_editor_requested = false;
+        match external_editor::edit(self.notes_input.get_value()) {
+            Ok(notes) => {
+                self.notes_input.set_value(&notes);
+                self.currently_editing_credential_field =
+                    Some(CurrentlyEditingCredentialField::Notes);
+            }
+            Err(err) => self.status_message = Some(err.to_string()),
+        }
+    }
+
+    /// Returns the text input that typed and pasted text goes to on the current screen.
+    pub fn get_focused_text_input(&mut self) -> Option<&mut TextInput> {
+        match self.current_screen {
+            CurrentScreen::NewPasswordRequiredScreen => Some(&mut self.new_password_input),
+            CurrentScreen::MasterPasswordRequiredScreen => Some(&mut self.master_password_input),
+            CurrentScreen::AttachmentPathScreen => Some(&mut self.attachment_path_input),
+            CurrentScreen::OtpImportScreen if self.otp_import_items.is_empty() => {
+                Some(&mut self.otp_import_input)
+            }
+            CurrentScreen::RotationScreen => self.rotation_defaults_input.as_mut(),
+            CurrentScreen::SearchScreen => Some(&mut self.search_input),
+            CurrentScreen::SpecificCredentialScreen => {
+                match self.currently_editing_credential_field.as_ref()? {
+                    CurrentlyEditingCredentialField::Website => Some(&mut self.website_input),
+                    CurrentlyEditingCredentialField::Email => Some(&mut self.email_input),
+                    CurrentlyEditingCredentialField::Username => Some(&mut self.username_input),
+                    CurrentlyEditingCredentialField::Password => Some(&mut self.password_input),
+                    CurrentlyEditingCredentialField::PasswordRules => {
+                        Some(&mut self.password_rules_input)
+                    }
+                    CurrentlyEditingCredentialField::Otp => Some(&mut self.otp_input),
+                    CurrentlyEditingCredentialField::Attachments => None,
+                    CurrentlyEditingCredentialField::Tags => Some(&mut self.tags_input),
+                    CurrentlyEditingCredentialField::Rotation => Some(&mut self.rotation_input),
+                    // The notes take line breaks, see `paste_text`.
+                    CurrentlyEditingCredentialField::Notes => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub fn open_attachment_prompt(&mut self, action: AttachmentActionEnum) {
+        let path = match action {
+            AttachmentActionEnum::Add => String::new(),
+            AttachmentActionEnum::Export => {
+                match self.attachments_input.get(self.selected_attachment_index) {
+                    Some(attachment) => attachment.get_file_name(),
+                    None => return,
+                }
+            }
+        };
+        self.attachment_path_input.set_value(&path);
+        self.attachment_action = Some(action);
+        self.current_screen = CurrentScreen::AttachmentPathScreen;
+    }
+
+    pub fn close_attachment_prompt(&mut self) {
+        self.attachment_path_input.clear();
+        self.attachment_action = None;
+        self.current_screen = CurrentScreen::SpecificCredentialScreen;
+    }
+
+    pub fn open_password_generator(&mut self) {
+        if !self.is_generating_master_password {
+            self.suggest_password_rules();
+        }
+        self.selected_generator_option = 0;
+        self.regenerate_password();
+        self.current_screen = CurrentScreen::PasswordGeneratorScreen;
+    }
+
+    /// Opens the generator from the new master password prompt, in passphrase mode since a
+    /// master password has to be remembered and typed.
+    pub fn open_master_password_generator(&mut self) {
+        self.is_generating_master_password = true;
+        self.credentials.settings.password_generator.mode = PasswordGeneratorModeEnum::Passphrase;
+        self.open_password_generator();
+    }
+
+    pub fn close_password_generator(&mut self) {
+        self.generated_password.clear();
+        self.current_screen = if self.is_generating_master_password {
+            CurrentScreen::NewPasswordRequiredScreen
+        } else {
+            CurrentScreen::SpecificCredentialScreen
+        };
+        self.is_generating_master_password = false;
+    }
+
+    /// Puts the generated password in the password field of the editor, or in the new
+    /// master password prompt.
+    pub fn accept_generated_password(&mut self) {
+        if self.generated_password.is_empty() {
+            return;
+        }
+        let password = std::mem::take(&mut self.generated_password);
+        if self.is_generating_master_password {
+            self.new_password_input.set_value(&password);
+        } else {
+            self.password_input.set_value(&password);
+            self.currently_editing_credential_field =
+                Some(CurrentlyEditingCredentialField::Password);
+        }
+        self.close_password_generator();
+    }
+
+    /// Returns the password rules of the credential being edited. They do not apply to a
+    /// new master password.
+    pub fn get_password_rules(&self) -> Option<PasswordRules> {
+        if self.is_generating_master_password
+            || self.password_rules_input.get_value().trim().is_empty()
+        {
+            return None;
+        }
+        PasswordRules::parse(self.password_rules_input.get_value()).ok()
+    }
+
+    /// Fills in the password rules of a known website from the bundled database, unless
+    /// rules were already entered.
+    pub fn suggest_password_rules(&mut self) {
+        if !self.password_rules_input.get_value().trim().is_empty() {
+            return;
+        }
+        if let Some(rules) = password_rules::find_rules_for_website(self.website_input.get_value())
+        {
+            self.password_rules_input.set_value(&rules);
+            self.status_message = Some(format!(
+                "Filled in the known password rules of {}",
+                self.website_input.get_value().trim()
+            ));
+        }
+    }
+
+    pub fn regenerate_password(&mut self) {
+        if !self.is_generating_master_password
+            && !self.password_rules_input.get_value().trim().is_empty()
+        {
+            if let Err(err) = PasswordRules::parse(self.password_rules_input.get_value()) {
+                self.generated_password.clear();
+                self.status_message = Some(err.to_string());
+                return;
+            }
+        }
+
+        match password_generator::generate_password(
+            &self.credentials.settings.password_generator,
+            self.get_password_rules().as_ref(),
+        ) {
+            Ok(password) => self.generated_password = password,
+            Err(err) => {
+                self.generated_password.clear();
+                self.status_message = Some(err.to_string());
+            }
+        }
+    }
+
+    pub fn get_generator_options(&self) -> &'static [GeneratorOptionEnum] {
+        GeneratorOptionEnum::get_options(self.credentials.settings.password_generator.mode)
+    }
+
+    pub fn get_selected_generator_option(&self) -> GeneratorOptionEnum {
+        let options = self.get_generator_options();
+        options[self.selected_generator_option.min(options.len() - 1)]
+    }
+
+    /// Types into the selected text option. A wordlist path is only loaded once it is
+    /// complete, on (^G).
+    pub fn push_generator_text(&mut self, value: char) {
+        let option = self.get_selected_generator_option();
+        if let Some(text) = option.get_text_mut(&mut self.credentials.settings.password_generator) {
+            text.push(value);
+            self.refresh_generated_password(option);
+        }
+    }
+
+    pub fn pop_generator_text(&mut self) {
+        let option = self.get_selected_generator_option();
+        if let Some(text) = option.get_text_mut(&mut self.credentials.settings.password_generator) {
+            text.pop();
+            self.refresh_generated_password(option);
+        }
+    }
+
+    fn refresh_generated_password(&mut self, option: GeneratorOptionEnum) {
+        if option == GeneratorOptionEnum::Wordlist {
+            self.generated_password.clear();
+        } else {
+            self.regenerate_password();
+        }
+    }
+
+    /// Changes the selected generator option and generates a new password with it.
+    pub fn adjust_generator_option(&mut self, delta: i64) {
+        let option = self.get_selected_generator_option();
+        option.adjust(&mut self.credentials.settings.password_generator, delta);
+        self.regenerate_password();
+    }
+
+    pub fn toggle_generator_option(&mut self) {
+        let option = self.get_selected_generator_option();
+        option.toggle(&mut self.credentials.settings.password_generator);
+        self.regenerate_password();
+    }
+
+    /// Encrypts the file at `attachment_path_input` and adds it to the credential being
+    /// edited. It is stored with the credential when the credential is saved.
+    pub fn add_attachment_from_path(&mut self) -> Result<String, Box<dyn Error>> {
+        let (file_name, content) =
+            attachments::read_attachment_file(self.attachment_path_input.get_value())?;
+        let (attachment, encrypted_attachment) =
+            vault_encryptor::encrypt_attachment(&file_name, &content);
+
+        self.attachment_store
+            .insert(attachment.get_id(), encrypted_attachment);
+        self.attachments_input.push(attachment);
+        self.selected_attachment_index = self.attachments_input.len() - 1;
+
+        Ok(format!("Attached {}", file_name))
+    }
+
+    pub fn export_selected_attachment(&self) -> Result<String, Box<dyn Error>> {
+        let attachment = self
+            .attachments_input
+            .get(self.selected_attachment_index)
+            .ok_or("No attachment selected")?;
+        let encrypted_attachment = self
+            .attachment_store
+            .get(&attachment.get_id())
+            .ok_or("The attachment content is missing from the vault")?;
+
+        let content = vault_encryptor::decrypt_attachment(attachment, encrypted_attachment)?;
+        attachments::write_attachment_file(self.attachment_path_input.get_value(), &content)?;
+
+        Ok(format!(
+            "Exported {} to {}",
+            attachment.get_file_name(),
+            self.attachment_path_input.get_value().trim()
+        ))
+    }
+
+    pub fn remove_selected_attachment(&mut self) {
+        if self.selected_attachment_index < self.attachments_input.len() {
+            self.attachments_input
+                .remove(self.selected_attachment_index);
+            self.selected_attachment_index = self.selected_attachment_index.saturating_sub(1);
+        }
+    }
+
+    pub fn select_next_attachment(&mut self) {
+        if self.selected_attachment_index + 1 < self.attachments_input.len() {
+            self.selected_attachment_index += 1;
+        }
+    }
+
+    pub fn select_previous_attachment(&mut self) {
+        self.selected_attachment_index = self.selected_attachment_index.saturating_sub(1);
+    }
+
+    pub fn load_otp_import(&mut self) -> Result<(), Box<dyn Error>> {
+        let accounts = otp_import::read_import_source(self.otp_import_input.get_value())?;
+
+        let (items, skipped) = otp_import::get_import_items(&self.credentials, accounts);
+        self.otp_import_items = items;
+        self.selected_otp_import_index = 0;
+        if skipped > 0 {
+            self.status_message = Some(format!(
+                "Skipped {} account(s) with the same issuer and account name as an earlier one",
+                skipped
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn toggle_selected_otp_import_item(&mut self) {
+        if let Some(item) = self
+            .otp_import_items
+            .get_mut(self.selected_otp_import_index)
+        {
+            item.accepted = !item.accepted;
+        }
+    }
+
+    pub fn accept_all_otp_import_items(&mut self) {
+        for item in &mut self.otp_import_items {
+            item.accepted = true;
+        }
+    }
+
+    /// Attaches the accepted accounts to their matching credentials or creates new ones.
+    /// Returns the number of merged accounts.
+    pub fn merge_otp_import(&mut self) -> usize {
+        let items = std::mem::take(&mut self.otp_import_items);
+        let previous = self.credentials.clone();
+        let mut merged = 0;
+
+        for item in items.into_iter().filter(|item| item.accepted) {
+            let mut credential = self
+                .credentials
+                .get_credential(&item.website, &item.email)
+                .unwrap_or_else(|| {
+                    Credential::new(Some(item.website), Some(item.email), None, None, None)
+                });
+            credential.otp = Some(item.otp);
+
+            self.credentials.add_or_update_credential(credential);
+            merged += 1;
+        }
+
+        if merged > 0 {
+            self.history.record(
+                format!("import of {} one-time password(s)", merged),
+                previous,
+            );
+        }
+
+        self.refresh_websites();
+        self.discard_otp_import();
+        merged
+    }
+
+    /// Reverts the last operation on the credentials.
+    pub fn undo(&mut self) {
+        match self.history.undo(&self.credentials) {
+            Some((description, credentials)) => {
+                self.credentials = credentials;
+                self.status_message = Some(format!("Undone: {}", description));
+                self.refresh_after_history_change();
+            }
+            None => {
+                self.status_message = Some("Nothing to undo".to_string());
+            }
+        }
+    }
+
+    /// Applies the last undone operation again.
+    pub fn redo(&mut self) {
+        match self.history.redo(&self.credentials) {
+            Some((description, credentials)) => {
+                self.credentials = credentials;
+                self.status_message = Some(format!("Redone: {}", description));
+                self.refresh_after_history_change();
+            }
+            None => {
+                self.status_message = Some("Nothing to redo".to_string());
+            }
+        }
+    }
+
+    fn refresh_after_history_change(&mut self) {
+        self.refresh_websites();
+        self.load_emails();
+        self.selected_email_index = std::cmp::min(
+            self.selected_email_index,
+            self.emails.len().saturating_sub(1),
+        );
+        self.clamp_selected_trash_index();
+        self.refresh_breach_counts();
+    }
+
+    pub fn discard_otp_import(&mut self) {
+        self.otp_import_input.clear();
+        self.otp_import_items.clear();
+        self.selected_otp_import_index = 0;
     }
 
     pub fn cycle_editing_credential(&mut self) {
         if let Some(edit_mode) = &self.currently_editing_credential_field {
             match edit_mode {
                 CurrentlyEditingCredentialField::Website => {
+                    self.suggest_password_rules();
                     self.currently_editing_credential_field =
                         Some(CurrentlyEditingCredentialField::Email)
                 }
@@ -219,6 +1438,26 @@ impl App {
                         Some(CurrentlyEditingCredentialField::Password)
                 }
                 CurrentlyEditingCredentialField::Password => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::PasswordRules)
+                }
+                CurrentlyEditingCredentialField::PasswordRules => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::Otp)
+                }
+                CurrentlyEditingCredentialField::Otp => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::Attachments)
+                }
+                CurrentlyEditingCredentialField::Attachments => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::Tags)
+                }
+                CurrentlyEditingCredentialField::Tags => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::Rotation)
+                }
+                CurrentlyEditingCredentialField::Rotation => {
                     self.currently_editing_credential_field =
                         Some(CurrentlyEditingCredentialField::Notes)
                 }
@@ -251,16 +1490,105 @@ impl App {
                     self.currently_editing_credential_field =
                         Some(CurrentlyEditingCredentialField::Username)
                 }
-                CurrentlyEditingCredentialField::Notes => {
+                CurrentlyEditingCredentialField::PasswordRules => {
                     self.currently_editing_credential_field =
                         Some(CurrentlyEditingCredentialField::Password)
                 }
+                CurrentlyEditingCredentialField::Otp => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::PasswordRules)
+                }
+                CurrentlyEditingCredentialField::Attachments => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::Otp)
+                }
+                CurrentlyEditingCredentialField::Tags => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::Attachments)
+                }
+                CurrentlyEditingCredentialField::Rotation => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::Tags)
+                }
+                CurrentlyEditingCredentialField::Notes => {
+                    self.currently_editing_credential_field =
+                        Some(CurrentlyEditingCredentialField::Rotation)
+                }
             };
         } else {
             self.currently_editing = Some(CurrentlyEditingCredentialField::Website);
         }
     }
 
+    /// Compares the credentials with those last loaded or saved.
+    pub fn refresh_unsaved_changes(&mut self) {
+        self.unsaved_changes = match &self.saved_credentials {
+            Some(saved) => !self.credentials.get_changes_from(saved).is_empty(),
+            // A new vault only exists once it is saved, but nothing is there to save before
+            // it has a master password.
+            None => !self.master_key.is_empty(),
+        };
+    }
+
+    /// Returns whether credentials were viewed or copied since the vault was last saved.
+    /// Access times alone are saved on quit without asking.
+    pub fn has_unsaved_access_times(&self) -> bool {
+        self.saved_credentials
+            .as_ref()
+            .is_some_and(|saved| self.credentials.is_accessed_since(saved))
+    }
+
+    /// Describes the unsaved changes, one line per kind of change.
+    pub fn get_unsaved_changes_summary(&self) -> Vec<String> {
+        let saved = match &self.saved_credentials {
+            Some(saved) => saved,
+            None => return vec!["The vault is new and has not been saved yet.".to_string()],
+        };
+
+        let changes = self.credentials.get_changes_from(saved);
+        let mut lines = Vec::new();
+        for (label, entries) in [
+            ("Added", &changes.added),
+            ("Changed", &changes.modified),
+            ("Removed", &changes.removed),
+        ] {
+            if !entries.is_empty() {
+                lines.push(format!(
+                    "{} ({}): {}",
+                    label,
+                    entries.len(),
+                    format_entries(entries)
+                ));
+            }
+        }
+        // Moving to and restoring from the trash already show as removed and added.
+        if changes.is_trash_changed && changes.added.is_empty() && changes.removed.is_empty() {
+            lines.push("The trash changed.".to_string());
+        }
+        if changes.are_settings_changed {
+            lines.push("The vault settings changed.".to_string());
+        }
+        lines
+    }
+
+    /// Writes the vault without quitting and reports the outcome in the footer.
+    pub fn save_vault(&mut self) {
+        if self.kdf_params.is_none() {
+            self.status_message = Some("Set a master password before saving".to_string());
+            return;
+        }
+        match self.save_changes() {
+            Ok(_) => {
+                self.saved_credentials = Some(self.credentials.clone());
+                self.unsaved_changes = false;
+                self.status_message = Some("Changes saved".to_string());
+            }
+            Err(err) => {
+                self.status_message = Some(format!("Could not save the vault: {}", err));
+            }
+        }
+    }
+
     pub fn save_changes(&self) -> Result<(), Box<dyn Error>> {
         // TODO: error handling
         let (encryption_params, encrypted_credentials) =
@@ -271,13 +1599,64 @@ impl App {
             encryption_params,
         );
 
-        let vault = Vault::new(vault_security_params, encrypted_credentials.as_slice());
+        // Attachments are stored as they are, dropping those no credential refers to anymore.
+        let attachment_ids = self.credentials.get_attachment_ids();
+        let encrypted_attachments = self
+            .attachment_store
+            .values()
+            .filter(|attachment| attachment_ids.contains(&attachment.get_id()))
+            .cloned()
+            .collect();
+
+        let vault = Vault::new(
+            vault_security_params,
+            encrypted_credentials.as_slice(),
+            encrypted_attachments,
+        );
 
         credentials_storage::store_vault(&vault)?;
-        println!("Changes saved");
         Ok(())
     }
 
+    /// Estimates the strength of the password being edited, taking the other fields of the
+    /// credential into account. Returns None while the password is empty.
+    pub fn get_password_strength(&self) -> Option<PasswordStrength> {
+        if self.password_input.is_empty() {
+            return None;
+        }
+        Some(PasswordStrength::estimate(
+            self.password_input.get_value(),
+            &[
+                self.website_input.get_value(),
+                self.email_input.get_value(),
+                self.username_input.get_value(),
+            ],
+        ))
+    }
+
+    pub fn get_new_password_strength(&self) -> PasswordStrength {
+        PasswordStrength::estimate(self.new_password_input.get_value(), &[])
+    }
+
+    /// Creates the vault key from the new master password, unless the password is weaker
+    /// than the configured minimum score.
+    pub fn set_new_master_password(&mut self) {
+        let strength = self.get_new_password_strength();
+        if strength.get_score() < self.config.min_master_password_score {
+            self.status_message = Some(format!(
+                "The master password is too weak ({}), it has to be at least {}",
+                strength.get_label(),
+                password_strength::get_score_label(self.config.min_master_password_score)
+            ));
+            return;
+        }
+
+        let password = self.new_password_input.take_value();
+        self.generate_initial_master_key_from_password(&password);
+        self.hide_secrets();
+        self.current_screen = CurrentScreen::MainCredentialScreen;
+    }
+
     pub fn generate_initial_master_key_from_password(&mut self, password: &str) {
         // TODO: Error handling
         let mut rng = OsRng;
@@ -297,3 +1676,40 @@ impl App {
         self.master_key = pbkdf::derive_key(self.kdf_params.as_ref().unwrap(), password).unwrap();
     }
 }
+
+/// The text a credential shows in the editor fields, in the order of `App::get_editor_values`.
+fn get_editor_values(credential: &Credential) -> Vec<String> {
+    vec![
+        credential.website.clone(),
+        credential.email.clone(),
+        credential.username.clone(),
+        credential.password.clone(),
+        credential.notes.clone(),
+        match &credential.otp {
+            Some(otp_params) => otp::to_uri(otp_params),
+            None => String::new(),
+        },
+        credential.tags.join(", "),
+        credential.password_rules.clone(),
+        match &credential.rotation {
+            Some(rotation) => rotation.to_input_string(),
+            None => String::new(),
+        },
+    ]
+}
+
+/// Lists the first few entries by website and email, such as "a.com / me@x.com, and 2 more".
+fn format_entries(entries: &[(String, String)]) -> String {
+    const SHOWN_ENTRIES: usize = 3;
+
+    let mut text = entries
+        .iter()
+        .take(SHOWN_ENTRIES)
+        .map(|(website, email)| format!("{} / {}", website, email))
+        .collect::<Vec<String>>()
+        .join(", ");
+    if entries.len() > SHOWN_ENTRIES {
+        text.push_str(&format!(", and {} more", entries.len() - SHOWN_ENTRIES));
+    }
+    text
+}
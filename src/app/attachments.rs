@@ -0,0 +1,50 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+/// Attachments are meant for small files such as recovery codes, keys and licenses.
+pub const MAX_ATTACHMENT_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Reads a file to attach and returns its name and content.
+pub fn read_attachment_file(path: &str) -> Result<(String, Vec<u8>), Box<dyn Error>> {
+    let path = Path::new(path.trim());
+
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", path.display()).into());
+    }
+    if metadata.len() > MAX_ATTACHMENT_SIZE {
+        return Err(format!(
+            "{} is larger than the {} MiB attachment limit",
+            path.display(),
+            MAX_ATTACHMENT_SIZE / 1024 / 1024
+        )
+        .into());
+    }
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+    let content = fs::read(path)?;
+
+    Ok((file_name, content))
+}
+
+/// Writes an exported attachment. The file is only readable by the owner and an existing
+/// file is never overwritten.
+pub fn write_attachment_file(path: &str, content: &[u8]) -> Result<(), Box<dyn Error>> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path.trim())?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::models::credentials::Credentials;
+
+/// The compact index starts with this, followed by sorted records of a SHA-1 hash and a
+/// big-endian u32 prevalence count.
+const INDEX_MAGIC: &[u8; 8] = b"KLXPWND1";
+const HASH_SIZE: usize = 20;
+const RECORD_SIZE: usize = HASH_SIZE + 4;
+
+#[derive(Debug)]
+pub struct BreachListError(String);
+
+impl fmt::Display for BreachListError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Breach list: {}", self.0)
+    }
+}
+
+impl Error for BreachListError {}
+
+impl From<std::io::Error> for BreachListError {
+    fn from(err: std::io::Error) -> Self {
+        BreachListError(err.to_string())
+    }
+}
+
+enum BreachListFormatEnum {
+    // The Have I Been Pwned download, "HASH:COUNT" lines ordered by hash.
+    Text { size: u64 },
+    Index { record_count: u64 },
+}
+
+/// A local pwned-passwords list, searched in place without loading it into memory.
+pub struct BreachList {
+    file: File,
+    format: BreachListFormatEnum,
+}
+
+impl BreachList {
+    pub fn open(path: &Path) -> Result<Self, BreachListError> {
+        let mut file = File::open(path)
+            .map_err(|err| BreachListError(format!("{}: {}", path.display(), err)))?;
+        let size = file.metadata()?.len();
+
+        let mut magic = [0u8; 8];
+        let is_index = size >= magic.len() as u64 && {
+            file.read_exact(&mut magic)?;
+            &magic == INDEX_MAGIC
+        };
+
+        let format = if is_index {
+            let data_size = size - INDEX_MAGIC.len() as u64;
+            if !data_size.is_multiple_of(RECORD_SIZE as u64) {
+                return Err(BreachListError("the index is truncated".to_string()));
+            }
+            BreachListFormatEnum::Index {
+                record_count: data_size / RECORD_SIZE as u64,
+            }
+        } else {
+            BreachListFormatEnum::Text { size }
+        };
+
+        Ok(BreachList { file, format })
+    }
+
+    /// Returns how often the password appears in breaches, or None if it does not.
+    /// The hash only ever exists in memory.
+    pub fn get_count(&mut self, password: &str) -> Result<Option<u64>, BreachListError> {
+        let hash: [u8; HASH_SIZE] = Sha1::digest(password.as_bytes()).into();
+        match self.format {
+            BreachListFormatEnum::Text { size } => self.search_text(&hash, size),
+            BreachListFormatEnum::Index { record_count } => self.search_index(&hash, record_count),
+        }
+    }
+
+    fn search_index(
+        &mut self,
+        hash: &[u8; HASH_SIZE],
+        record_count: u64,
+    ) -> Result<Option<u64>, BreachListError> {
+        let (mut low, mut high) = (0, record_count);
+        let mut record = [0u8; RECORD_SIZE];
+        while low < high {
+            let middle = low + (high - low) / 2;
+            self.file.seek(SeekFrom::Start(
+                INDEX_MAGIC.len() as u64 + middle * RECORD_SIZE as u64,
+            ))?;
+            self.file.read_exact(&mut record)?;
+            match record[..HASH_SIZE].cmp(&hash[..]) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => {
+                    let count: [u8; 4] = record[HASH_SIZE..].try_into().unwrap();
+                    return Ok(Some(u32::from_be_bytes(count) as u64));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Binary search over byte offsets. Each step reads the first whole line that starts at
+    /// or after the middle offset.
+    fn search_text(
+        &mut self,
+        hash: &[u8; HASH_SIZE],
+        size: u64,
+    ) -> Result<Option<u64>, BreachListError> {
+        let (mut low, mut high) = (0, size);
+        let mut line = String::new();
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let line_start = self.find_line_start(middle)?;
+            if line_start >= high {
+                high = middle;
+                continue;
+            }
+
+            self.file.seek(SeekFrom::Start(line_start))?;
+            let mut reader = BufReader::new(&self.file);
+            line.clear();
+            let line_size = reader.read_line(&mut line)? as u64;
+            let (line_hash, count) = parse_text_line(&line)?;
+
+            match line_hash[..].cmp(&hash[..]) {
+                std::cmp::Ordering::Less => low = line_start + line_size,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Ok(Some(count)),
+            }
+        }
+        Ok(None)
+    }
+
+    fn find_line_start(&mut self, offset: u64) -> Result<u64, BreachListError> {
+        if offset == 0 {
+            return Ok(0);
+        }
+        self.file.seek(SeekFrom::Start(offset - 1))?;
+        let mut reader = BufReader::new(&self.file);
+        let mut skipped = Vec::new();
+        let skipped_size = reader.read_until(b'\n', &mut skipped)? as u64;
+        Ok(offset - 1 + skipped_size)
+    }
+}
+
+fn parse_text_line(line: &str) -> Result<([u8; HASH_SIZE], u64), BreachListError> {
+    let invalid = || BreachListError(format!("invalid line \"{}\"", line.trim()));
+    let (hash, count) = line.trim().split_once(':').ok_or_else(invalid)?;
+    let hash = data_encoding::HEXUPPER_PERMISSIVE
+        .decode(hash.as_bytes())
+        .map_err(|_| invalid())?;
+    let hash: [u8; HASH_SIZE] = hash.try_into().map_err(|_| invalid())?;
+    let count = count.parse().map_err(|_| invalid())?;
+    Ok((hash, count))
+}
+
+/// Converts the Have I Been Pwned text download into the compact index, which is about half
+/// the size. The text file has to be ordered by hash.
+pub fn build_index(source: &Path, destination: &Path) -> Result<u64, BreachListError> {
+    let reader = BufReader::new(File::open(source)?);
+    let mut writer = BufWriter::new(File::create(destination)?);
+    writer.write_all(INDEX_MAGIC)?;
+
+    let mut previous: Option<[u8; HASH_SIZE]> = None;
+    let mut record_count = 0;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (hash, count) = parse_text_line(&line)?;
+        if previous.is_some_and(|previous| previous >= hash) {
+            return Err(BreachListError(
+                "the source is not ordered by hash".to_string(),
+            ));
+        }
+        writer.write_all(&hash)?;
+        writer.write_all(&(count.min(u32::MAX as u64) as u32).to_be_bytes())?;
+        previous = Some(hash);
+        record_count += 1;
+    }
+
+    writer.flush()?;
+    Ok(record_count)
+}
+
+/// Checks every password of the vault and returns the prevalence of the breached ones,
+/// by website and email.
+pub fn get_breach_counts(
+    credentials: &Credentials,
+    path: &Path,
+) -> Result<HashMap<(String, String), u64>, BreachListError> {
+    let mut breach_list = BreachList::open(path)?;
+    let mut counts = HashMap::new();
+    for credential in &credentials.credentials {
+        if credential.password.is_empty() {
+            continue;
+        }
+        if let Some(count) = breach_list.get_count(&credential.password)? {
+            counts.insert(
+                (credential.website.clone(), credential.email.clone()),
+                count,
+            );
+        }
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_text_list(path: &Path, passwords: &[(&str, u64)]) {
+        let mut lines: Vec<String> = passwords
+            .iter()
+            .map(|(password, count)| {
+                let hash = Sha1::digest(password.as_bytes());
+                format!("{}:{}", data_encoding::HEXUPPER.encode(&hash), count)
+            })
+            .collect();
+        lines.sort();
+        std::fs::write(path, lines.join("\r\n") + "\r\n").unwrap();
+    }
+
+    #[test]
+    fn test_search_text_and_index() {
+        let directory = std::env::temp_dir().join(format!("keylox-breach-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let text_path = directory.join("pwned.txt");
+        let index_path = directory.join("pwned.bin");
+
+        let passwords: Vec<(String, u64)> = (0..500)
+            .map(|i| (format!("password{}", i), i + 1))
+            .collect();
+        let passwords: Vec<(&str, u64)> = passwords.iter().map(|(p, c)| (p.as_str(), *c)).collect();
+        write_text_list(&text_path, &passwords);
+        assert_eq!(build_index(&text_path, &index_path).unwrap(), 500);
+
+        for path in [&text_path, &index_path] {
+            let mut breach_list = BreachList::open(path).unwrap();
+            for (password, count) in &passwords {
+                assert_eq!(breach_list.get_count(password).unwrap(), Some(*count));
+            }
+            assert_eq!(breach_list.get_count("not breached").unwrap(), None);
+        }
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+use data_encoding::BASE64;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct ClipboardError(String);
+
+impl fmt::Display for ClipboardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Clipboard: {}", self.0)
+    }
+}
+
+impl Error for ClipboardError {}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ClipboardMethodEnum {
+    System,
+    // The OSC 52 escape sequence, which asks the terminal to set its clipboard. It works over
+    // SSH, but the clipboard cannot be read back.
+    Osc52,
+}
+
+/// A value that is cleared from the clipboard once its time is up. Only its hash is kept.
+struct PendingClear {
+    clear_at: Instant,
+    method: ClipboardMethodEnum,
+    value_hash: [u8; 32],
+}
+
+pub struct Clipboard {
+    // Kept open, since on Linux the copied text disappears with it. None until the first copy
+    // and when there is no system clipboard.
+    system: Option<arboard::Clipboard>,
+    pending_clear: Option<PendingClear>,
+}
+
+impl Clipboard {
+    pub fn new() -> Self {
+        Clipboard {
+            system: None,
+            pending_clear: None,
+        }
+    }
+
+    /// Copies the value with the system clipboard, or with OSC 52 over SSH and when there is no
+    /// system clipboard. A zero `clear_after` keeps the value.
+    pub fn copy(
+        &mut self,
+        value: &str,
+        clear_after: Duration,
+    ) -> Result<ClipboardMethodEnum, ClipboardError> {
+        let method = if is_remote_session() || !self.open_system() {
+            write_osc52(value)?;
+            ClipboardMethodEnum::Osc52
+        } else {
+            set_system_text(self.system.as_mut().unwrap(), value)?;
+            ClipboardMethodEnum::System
+        };
+
+        self.pending_clear = (!clear_after.is_zero()).then(|| PendingClear {
+            clear_at: Instant::now() + clear_after,
+            method,
+            value_hash: hash(value),
+        });
+        Ok(method)
+    }
+
+    /// Clears the clipboard once the time of the copied value is up.
+    pub fn clear_if_expired(&mut self, now: Instant) -> Result<(), ClipboardError> {
+        match &self.pending_clear {
+            Some(pending_clear) if now >= pending_clear.clear_at => self.clear(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Clears a copied value at once, on exit.
+    pub fn clear(&mut self) -> Result<(), ClipboardError> {
+        let pending_clear = match self.pending_clear.take() {
+            Some(pending_clear) => pending_clear,
+            None => return Ok(()),
+        };
+        match pending_clear.method {
+            ClipboardMethodEnum::System => {
+                let system = match self.system.as_mut() {
+                    Some(system) => system,
+                    None => return Ok(()),
+                };
+                // Something copied since then is left alone.
+                let is_ours = system
+                    .get_text()
+                    .is_ok_and(|text| hash(&text) == pending_clear.value_hash);
+                if is_ours {
+                    system.clear().map_err(to_error)?;
+                }
+                Ok(())
+            }
+            // The terminal clipboard cannot be read, so it is cleared regardless.
+            ClipboardMethodEnum::Osc52 => write_osc52(""),
+        }
+    }
+
+    /// Returns whether a system clipboard is available, connecting to it on first use.
+    fn open_system(&mut self) -> bool {
+        if self.system.is_none() {
+            self.system = arboard::Clipboard::new().ok();
+        }
+        self.system.is_some()
+    }
+}
+
+fn to_error(err: arboard::Error) -> ClipboardError {
+    ClipboardError(err.to_string())
+}
+
+fn hash(value: &str) -> [u8; 32] {
+    Sha256::digest(value.as_bytes()).into()
+}
+
+#[cfg(target_os = "macos")]
+fn set_system_text(system: &mut arboard::Clipboard, value: &str) -> Result<(), ClipboardError> {
+    use arboard::SetExtApple;
+    system
+        .set()
+        .exclude_from_history()
+        .text(value)
+        .map_err(to_error)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn set_system_text(system: &mut arboard::Clipboard, value: &str) -> Result<(), ClipboardError> {
+    system.set_text(value).map_err(to_error)
+}
+
+/// The system clipboard of an SSH session is the one of the remote machine, if any.
+fn is_remote_session() -> bool {
+    env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some()
+}
+
+/// Returns the escape sequence that sets the clipboard of the terminal to the value. Inside
+/// tmux or screen it is wrapped so that they pass it on to the outer terminal.
+fn get_osc52_sequence(value: &str, is_tmux: bool, is_screen: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", BASE64.encode(value.as_bytes()));
+    if is_tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else if is_screen {
+        format!("\x1bP{}\x1b\\", sequence)
+    } else {
+        sequence
+    }
+}
+
+fn write_osc52(value: &str) -> Result<(), ClipboardError> {
+    let is_tmux = env::var_os("TMUX").is_some();
+    let is_screen = env::var("TERM").is_ok_and(|term| term.starts_with("screen")) && !is_tmux;
+    let mut stdout = io::stdout();
+    stdout
+        .write_all(get_osc52_sequence(value, is_tmux, is_screen).as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|err| ClipboardError(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(
+            get_osc52_sequence("hunter2", false, false),
+            "\x1b]52;c;aHVudGVyMg==\x07"
+        );
+        assert_eq!(
+            get_osc52_sequence("hunter2", true, false),
+            "\x1bPtmux;\x1b\x1b]52;c;aHVudGVyMg==\x07\x1b\\"
+        );
+        assert_eq!(
+            get_osc52_sequence("hunter2", false, true),
+            "\x1bP\x1b]52;c;aHVudGVyMg==\x07\x1b\\"
+        );
+        assert_eq!(get_osc52_sequence("", false, false), "\x1b]52;c;\x07");
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::app::password_strength::MAX_SCORE;
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Settings that apply before a vault is unlocked, read from `config.json` next to the vault.
+/// Missing settings, or a missing file, fall back to the defaults.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AppConfig {
+    // The minimum strength score, from 0 to 4, of a new master password.
+    #[serde(default = "default_min_master_password_score")]
+    pub min_master_password_score: u8,
+    // A local Have I Been Pwned list, as the sorted text download or a compact index.
+    // Empty disables the breach check.
+    #[serde(default)]
+    pub breach_list_path: String,
+    // Minutes the derived key stays in the Linux kernel keyring after an unlock, so that later
+    // runs in the same login session skip the master password. Zero disables the cache.
+    #[serde(default)]
+    pub keyring_timeout_minutes: u32,
+    // Seconds after which a copied value is cleared from the clipboard. Zero keeps it.
+    #[serde(default = "default_clipboard_clear_seconds")]
+    pub clipboard_clear_seconds: u32,
+    // Seconds a revealed password stays in clear text before it is masked again. Zero keeps it
+    // revealed until it is hidden with the same key.
+    #[serde(default)]
+    pub password_reveal_seconds: u32,
+}
+
+fn default_min_master_password_score() -> u8 {
+    3
+}
+
+fn default_clipboard_clear_seconds() -> u32 {
+    30
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            min_master_password_score: default_min_master_password_score(),
+            breach_list_path: String::new(),
+            keyring_timeout_minutes: 0,
+            clipboard_clear_seconds: default_clipboard_clear_seconds(),
+            password_reveal_seconds: 0,
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        if !Path::new(CONFIG_FILE_NAME).exists() {
+            return Ok(AppConfig::default());
+        }
+        let reader = BufReader::new(File::open(CONFIG_FILE_NAME)?);
+        let mut config: AppConfig = serde_json::from_reader(reader)?;
+        config.min_master_password_score = config.min_master_password_score.min(MAX_SCORE);
+        Ok(config)
+    }
+}
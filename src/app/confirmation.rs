@@ -0,0 +1,141 @@
+/// An action that waits for the user to confirm it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ConfirmationActionEnum {
+    Cancel,
+    MoveToTrash,
+    PurgeFromTrash,
+    RemoveAttachment,
+    DiscardChanges,
+    OverwriteCredential,
+    MergeOtpImport,
+    SaveAndQuit,
+    QuitWithoutSaving,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConfirmationChoice {
+    pub key: Option<char>, // the key that picks the choice, Cancel is picked with Esc.
+    pub label: &'static str,
+    pub action: ConfirmationActionEnum,
+}
+
+/// A question shown in a popup over the current screen. The first choice cancels and is
+/// selected at first, so that pressing Return right away never destroys anything.
+#[derive(Clone, Debug)]
+pub struct Confirmation {
+    pub title: String,
+    pub message: String,
+    pub choices: Vec<ConfirmationChoice>,
+    pub selected_choice: usize,
+}
+
+impl Confirmation {
+    /// A yes-or-no question: Y performs the action, and N or Esc cancels.
+    pub fn new(
+        title: &str,
+        message: String,
+        label: &'static str,
+        action: ConfirmationActionEnum,
+    ) -> Self {
+        Confirmation::with_choices(title, message, vec![('y', label, action)])
+    }
+
+    /// A question with several actions, each picked with its key, besides Cancel.
+    pub fn with_choices(
+        title: &str,
+        message: String,
+        choices: Vec<(char, &'static str, ConfirmationActionEnum)>,
+    ) -> Self {
+        let cancel = ConfirmationChoice {
+            key: None,
+            label: "Cancel",
+            action: ConfirmationActionEnum::Cancel,
+        };
+        let choices = choices
+            .into_iter()
+            .map(|(key, label, action)| ConfirmationChoice {
+                key: Some(key),
+                label,
+                action,
+            });
+
+        Confirmation {
+            title: title.to_string(),
+            message,
+            choices: std::iter::once(cancel).chain(choices).collect(),
+            selected_choice: 0,
+        }
+    }
+
+    pub fn select_next_choice(&mut self) {
+        self.selected_choice = (self.selected_choice + 1) % self.choices.len();
+    }
+
+    pub fn select_previous_choice(&mut self) {
+        self.selected_choice = (self.selected_choice + self.choices.len() - 1) % self.choices.len();
+    }
+
+    pub fn get_selected_action(&self) -> ConfirmationActionEnum {
+        self.choices[self.selected_choice].action
+    }
+
+    /// Returns the action picked with the key. N cancels unless a choice uses it.
+    pub fn get_action_for_key(&self, key: char) -> Option<ConfirmationActionEnum> {
+        let key = key.to_ascii_lowercase();
+        match self.choices.iter().find(|choice| choice.key == Some(key)) {
+            Some(choice) => Some(choice.action),
+            None if key == 'n' => Some(ConfirmationActionEnum::Cancel),
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choices() {
+        let mut confirmation = Confirmation::new(
+            "Move to the trash",
+            "Move github.com / me@x.com to the trash?".to_string(),
+            "Move to the trash",
+            ConfirmationActionEnum::MoveToTrash,
+        );
+        assert_eq!(
+            confirmation.get_selected_action(),
+            ConfirmationActionEnum::Cancel
+        );
+        assert_eq!(
+            confirmation.get_action_for_key('Y'),
+            Some(ConfirmationActionEnum::MoveToTrash)
+        );
+        assert_eq!(
+            confirmation.get_action_for_key('n'),
+            Some(ConfirmationActionEnum::Cancel)
+        );
+        assert_eq!(confirmation.get_action_for_key('x'), None);
+        confirmation.select_previous_choice();
+        assert_eq!(
+            confirmation.get_selected_action(),
+            ConfirmationActionEnum::MoveToTrash
+        );
+
+        let confirmation = Confirmation::with_choices(
+            "Unsaved changes",
+            "Would you like to save your changes?".to_string(),
+            vec![
+                ('y', "Save and quit", ConfirmationActionEnum::SaveAndQuit),
+                (
+                    'n',
+                    "Quit without saving",
+                    ConfirmationActionEnum::QuitWithoutSaving,
+                ),
+            ],
+        );
+        assert_eq!(
+            confirmation.get_action_for_key('n'),
+            Some(ConfirmationActionEnum::QuitWithoutSaving)
+        );
+    }
+}
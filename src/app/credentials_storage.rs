@@ -4,7 +4,7 @@ use rmp_serde;
 use serde_json;
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{error::Error, io::BufReader};
 
 const USE_JSON: bool = true;
@@ -34,6 +34,11 @@ pub fn are_credentials_present() -> bool {
     return Path::new(&get_filename()).exists();
 }
 
+/// Returns the absolute path of the vault, which identifies it to the agent.
+pub fn get_vault_path() -> std::io::Result<PathBuf> {
+    std::fs::canonicalize(get_filename())
+}
+
 pub fn load_credentials() -> Result<Option<Vault>, Box<dyn Error>> {
     if !are_credentials_present() {
         return Ok(None);
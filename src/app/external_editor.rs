@@ -0,0 +1,155 @@
+use rand::rngs::OsRng;
+use rand::Rng;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Used when neither VISUAL nor EDITOR is set.
+const DEFAULT_EDITOR: &str = "vi";
+
+#[derive(Debug)]
+pub struct ExternalEditorError(String);
+
+impl fmt::Display for ExternalEditorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "External editor: {}", self.0)
+    }
+}
+
+impl Error for ExternalEditorError {}
+
+impl From<io::Error> for ExternalEditorError {
+    fn from(err: io::Error) -> Self {
+        ExternalEditorError(err.to_string())
+    }
+}
+
+/// Returns the editor command of `$VISUAL` or `$EDITOR`, split into the program and its
+/// arguments, such as `code --wait`.
+fn get_editor_command() -> Vec<String> {
+    let editor = env::var("VISUAL")
+        .ok()
+        .or_else(|| env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    editor.split_whitespace().map(str::to_string).collect()
+}
+
+/// Returns a directory whose files live in memory only, so that the text never reaches a
+/// disk: `/dev/shm`, or the runtime directory of the user.
+#[cfg(target_os = "linux")]
+fn get_memory_directory() -> Result<PathBuf, ExternalEditorError> {
+    let candidates = [
+        Some(PathBuf::from("/dev/shm")),
+        env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|directory| is_memory_backed(directory))
+        .ok_or_else(|| ExternalEditorError("no memory-backed directory (tmpfs) found".to_string()))
+}
+
+#[cfg(target_os = "linux")]
+fn is_memory_backed(directory: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    // From linux/magic.h.
+    const TMPFS_MAGIC: u32 = 0x0102_1994;
+    const RAMFS_MAGIC: u32 = 0x8584_58f6;
+
+    let path = match CString::new(directory.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return false,
+    };
+    // SAFETY: an all-zero statfs is valid, and the path is NUL-terminated.
+    let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(path.as_ptr(), &mut stats) } != 0 {
+        return false;
+    }
+    matches!(stats.f_type as u32, TMPFS_MAGIC | RAMFS_MAGIC)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_memory_directory() -> Result<PathBuf, ExternalEditorError> {
+    Err(ExternalEditorError(
+        "only available on Linux, where a memory-backed directory can be checked".to_string(),
+    ))
+}
+
+/// Creates a file only the user can read, under a random name that cannot already exist.
+#[cfg(unix)]
+fn create_private_file(directory: &Path, text: &str) -> Result<PathBuf, ExternalEditorError> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let path = directory.join(format!("keylox-{:016x}.txt", OsRng.gen::<u64>()));
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .custom_flags(libc::O_NOFOLLOW)
+        .open(&path)?;
+    file.write_all(text.as_bytes())?;
+    // Ends the last line as editors expect, the line break is removed again after editing.
+    file.write_all(b"\n")?;
+    Ok(path)
+}
+
+#[cfg(not(unix))]
+fn create_private_file(_directory: &Path, _text: &str) -> Result<PathBuf, ExternalEditorError> {
+    Err(ExternalEditorError("only available on Unix".to_string()))
+}
+
+/// Overwrites the file with zeros before removing it, so that the text does not stay behind in
+/// freed memory.
+fn remove_securely(path: &Path) -> io::Result<()> {
+    use std::io::Write;
+
+    let length = fs::metadata(path)?.len();
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    file.write_all(&vec![0; length as usize])?;
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)
+}
+
+/// Opens the text in the editor of the user and returns the edited text. The terminal has to
+/// be handed over to the editor before. The temporary file is removed in any case.
+pub fn edit(text: &str) -> Result<String, ExternalEditorError> {
+    let directory = get_memory_directory()?;
+    let path = create_private_file(&directory, text)?;
+    let result = run_editor(&path);
+    let removal = remove_securely(&path);
+
+    let edited = result?;
+    removal?;
+    Ok(edited)
+}
+
+fn run_editor(path: &Path) -> Result<String, ExternalEditorError> {
+    let command = get_editor_command();
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .arg(path)
+        .status()
+        .map_err(|err| ExternalEditorError(format!("could not run {}: {}", command[0], err)))?;
+    if !status.success() {
+        return Err(ExternalEditorError(format!(
+            "{} exited with {}, the notes were not changed",
+            command[0], status
+        )));
+    }
+
+    let mut edited = fs::read_to_string(path)?;
+    // Editors end the last line with a line break.
+    if edited.ends_with('\n') {
+        edited.pop();
+    }
+    Ok(edited)
+}
@@ -0,0 +1,336 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::models::credentials::Credentials;
+use super::password_strength::PasswordStrength;
+
+// Passwords with a lower strength score are reported as weak.
+const MIN_STRENGTH_SCORE: u8 = 3;
+// Different passwords within this many single-character edits are reported as near-duplicates.
+const NEAR_DUPLICATE_DISTANCE: usize = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthIssueEnum {
+    Breached,
+    EmptyPassword,
+    Reused,
+    Weak,
+    NearDuplicate,
+    Old,
+    InsecureUrl,
+}
+
+impl HealthIssueEnum {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            HealthIssueEnum::Breached => "Breached passwords",
+            HealthIssueEnum::EmptyPassword => "Empty passwords",
+            HealthIssueEnum::Reused => "Reused passwords",
+            HealthIssueEnum::Weak => "Weak passwords",
+            HealthIssueEnum::NearDuplicate => "Near-duplicate passwords",
+            HealthIssueEnum::Old => "Old passwords",
+            HealthIssueEnum::InsecureUrl => "Insecure http:// websites",
+        }
+    }
+}
+
+/// One problem with one credential. Passwords never appear in a finding.
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthFinding {
+    pub issue: HealthIssueEnum,
+    pub website: String,
+    pub email: String,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthReport {
+    pub max_password_age_days: u32,
+    // Sorted by issue, then by website and email.
+    pub findings: Vec<HealthFinding>,
+}
+
+impl HealthReport {
+    /// Analyzes the vault. `breach_counts` holds the prevalence of breached passwords by
+    /// website and email, as returned by `breach_check::get_breach_counts`.
+    pub fn analyze(
+        credentials: &Credentials,
+        breach_counts: &HashMap<(String, String), u64>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let max_password_age_days = credentials.settings.password_max_age_days;
+        let mut findings = Vec::new();
+
+        let mut entries_by_password: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, credential) in credentials.credentials.iter().enumerate() {
+            if !credential.password.is_empty() {
+                entries_by_password
+                    .entry(credential.password.as_str())
+                    .or_default()
+                    .push(i);
+            }
+        }
+
+        let near_duplicates = get_near_duplicates(credentials);
+
+        for (i, credential) in credentials.credentials.iter().enumerate() {
+            let mut add = |issue: HealthIssueEnum, detail: String| {
+                findings.push(HealthFinding {
+                    issue,
+                    website: credential.website.clone(),
+                    email: credential.email.clone(),
+                    detail,
+                });
+            };
+
+            if credential
+                .website
+                .trim()
+                .to_lowercase()
+                .starts_with("http://")
+            {
+                add(
+                    HealthIssueEnum::InsecureUrl,
+                    "the website does not use https".to_string(),
+                );
+            }
+
+            if credential.password.is_empty() {
+                add(HealthIssueEnum::EmptyPassword, "no password".to_string());
+                continue;
+            }
+
+            let key = (credential.website.clone(), credential.email.clone());
+            if let Some(count) = breach_counts.get(&key) {
+                add(
+                    HealthIssueEnum::Breached,
+                    format!("seen {} times in data breaches", count),
+                );
+            }
+
+            let others: Vec<String> = entries_by_password[credential.password.as_str()]
+                .iter()
+                .filter(|&&j| j != i)
+                .map(|&j| format_entry(credentials, j))
+                .collect();
+            if !others.is_empty() {
+                add(
+                    HealthIssueEnum::Reused,
+                    format!("also used by {}", others.join(", ")),
+                );
+            }
+
+            let strength = PasswordStrength::estimate(
+                &credential.password,
+                &[&credential.website, &credential.email, &credential.username],
+            );
+            if strength.get_score() < MIN_STRENGTH_SCORE {
+                let mut detail = format!(
+                    "{}, {} to crack",
+                    strength.get_label(),
+                    strength.get_crack_time()
+                );
+                if let Some(feedback) = strength.get_feedback() {
+                    detail.push_str(&format!(" ({})", feedback));
+                }
+                add(HealthIssueEnum::Weak, detail);
+            }
+
+            let similar: Vec<String> = near_duplicates[i]
+                .iter()
+                .map(|&j| format_entry(credentials, j))
+                .collect();
+            if !similar.is_empty() {
+                add(
+                    HealthIssueEnum::NearDuplicate,
+                    format!("similar to {}", similar.join(", ")),
+                );
+            }
+
+            // Vaults written before the timestamps existed only have the creation date, if any.
+            let changed_at = credential.password_modified_at.or(credential.created_at);
+            if let Some(changed_at) = changed_at {
+                let age_days = (now - changed_at).num_days();
+                if max_password_age_days > 0 && age_days > max_password_age_days as i64 {
+                    add(
+                        HealthIssueEnum::Old,
+                        format!("changed {} days ago", age_days),
+                    );
+                }
+            }
+        }
+
+        findings.sort_by(|a, b| {
+            (a.issue, a.website.to_lowercase(), a.email.to_lowercase()).cmp(&(
+                b.issue,
+                b.website.to_lowercase(),
+                b.email.to_lowercase(),
+            ))
+        });
+
+        HealthReport {
+            max_password_age_days,
+            findings,
+        }
+    }
+
+    /// Returns the number of findings of each issue that occurs, in report order.
+    pub fn get_counts(&self) -> Vec<(HealthIssueEnum, usize)> {
+        let mut counts: Vec<(HealthIssueEnum, usize)> = Vec::new();
+        for finding in &self.findings {
+            match counts.last_mut() {
+                Some((issue, count)) if *issue == finding.issue => *count += 1,
+                _ => counts.push((finding.issue, 1)),
+            }
+        }
+        counts
+    }
+
+    pub fn to_text(&self) -> String {
+        if self.findings.is_empty() {
+            return "No problems found.\n".to_string();
+        }
+
+        let mut text = format!("{} problem(s) found.\n", self.findings.len());
+        for (issue, count) in self.get_counts() {
+            text.push_str(&format!("\n{} ({})\n", issue.get_name(), count));
+            for finding in self.findings.iter().filter(|f| f.issue == issue) {
+                text.push_str(&format!(
+                    "  {} / {}: {}\n",
+                    finding.website, finding.email, finding.detail
+                ));
+            }
+        }
+        text
+    }
+}
+
+fn format_entry(credentials: &Credentials, index: usize) -> String {
+    let credential = &credentials.credentials[index];
+    format!("{} / {}", credential.website, credential.email)
+}
+
+/// Returns, for each credential, the indices of those with a different password within
+/// `NEAR_DUPLICATE_DISTANCE` edits, in vault order. Passwords whose lengths differ by more
+/// than the distance cannot be that close, so only passwords of similar lengths are compared.
+fn get_near_duplicates(credentials: &Credentials) -> Vec<Vec<usize>> {
+    let mut by_length: Vec<(usize, usize)> = credentials
+        .credentials
+        .iter()
+        .enumerate()
+        .filter(|(_, credential)| !credential.password.is_empty())
+        .map(|(i, credential)| (credential.password.chars().count(), i))
+        .collect();
+    by_length.sort();
+
+    let mut near_duplicates = vec![Vec::new(); credentials.credentials.len()];
+    for (a, &(length, i)) in by_length.iter().enumerate() {
+        for &(other_length, j) in &by_length[a + 1..] {
+            if other_length - length > NEAR_DUPLICATE_DISTANCE {
+                break;
+            }
+            let (password, other) = (
+                &credentials.credentials[i].password,
+                &credentials.credentials[j].password,
+            );
+            if password != other && get_edit_distance(password, other) <= NEAR_DUPLICATE_DISTANCE {
+                near_duplicates[i].push(j);
+                near_duplicates[j].push(i);
+            }
+        }
+    }
+    for indices in &mut near_duplicates {
+        indices.sort();
+    }
+    near_duplicates
+}
+
+/// Returns the Levenshtein distance between two strings, counted in characters.
+pub fn get_edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let substitution = previous[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            current[j] = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::models::credentials::Credential;
+    use chrono::Duration;
+
+    fn add(credentials: &mut Credentials, website: &str, password: &str) {
+        credentials.add_or_update_credential(Credential::new(
+            Some(website.to_string()),
+            Some("me@example.com".to_string()),
+            None,
+            Some(password.to_string()),
+            None,
+        ));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(get_edit_distance("kitten", "sitting"), 3);
+        assert_eq!(get_edit_distance("", "abc"), 3);
+        assert_eq!(get_edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_near_duplicates() {
+        let mut credentials = Credentials::new();
+        add(&mut credentials, "a.com", "correct-horse");
+        add(&mut credentials, "b.com", "correct-horse!!");
+        add(&mut credentials, "c.com", "correct-horse!!!");
+        add(&mut credentials, "d.com", "");
+        assert_eq!(
+            get_near_duplicates(&credentials),
+            vec![vec![1], vec![0, 2], vec![1], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_analyze() {
+        let mut credentials = Credentials::new();
+        add(&mut credentials, "a.com", "Tr0ub4dor&3-horse-staple!");
+        add(&mut credentials, "b.com", "Tr0ub4dor&3-horse-staple!");
+        add(&mut credentials, "c.com", "Tr0ub4dor&3-horse-staple?1");
+        add(&mut credentials, "http://d.com", "password");
+        add(&mut credentials, "e.com", "");
+        credentials.credentials[0].password_modified_at = Some(Utc::now() - Duration::days(400));
+
+        let mut breach_counts = HashMap::new();
+        breach_counts.insert(("b.com".to_string(), "me@example.com".to_string()), 42);
+        let report = HealthReport::analyze(&credentials, &breach_counts, Utc::now());
+        let count = |issue| {
+            report
+                .get_counts()
+                .into_iter()
+                .find(|(i, _)| *i == issue)
+                .map_or(0, |(_, count)| count)
+        };
+        assert_eq!(count(HealthIssueEnum::Reused), 2);
+        assert_eq!(count(HealthIssueEnum::NearDuplicate), 3);
+        assert_eq!(count(HealthIssueEnum::Weak), 1);
+        assert_eq!(count(HealthIssueEnum::Old), 1);
+        assert_eq!(count(HealthIssueEnum::InsecureUrl), 1);
+        assert_eq!(count(HealthIssueEnum::EmptyPassword), 1);
+        assert_eq!(count(HealthIssueEnum::Breached), 1);
+        assert_eq!(report.findings[0].issue, HealthIssueEnum::Breached);
+        assert!(!serde_json::to_string(&report).unwrap().contains("horse"));
+        assert!(report.to_text().contains("Reused passwords (2)"));
+    }
+}
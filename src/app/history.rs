@@ -0,0 +1,154 @@
+use super::models::credentials::Credentials;
+
+/// The number of operations that can be undone.
+const MAX_HISTORY_SIZE: usize = 100;
+
+/// A vault operation together with the credentials as they were on the other side of it.
+struct HistoryEntry {
+    description: String,
+    credentials: Credentials,
+}
+
+/// Undo and redo stacks for the operations made on the credentials during a session.
+/// Every entry keeps a full copy of the credentials, which also covers the trash and the
+/// settings. Attachment contents are not copied, only their keys and ids. HOTP counters and
+/// access times are not part of any operation, so a restored copy keeps the current ones.
+pub struct History {
+    undo_stack: Vec<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        History {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    /// Records an operation, given the credentials as they were before it was applied.
+    /// Recording a new operation drops everything that could be redone.
+    pub fn record(&mut self, description: String, previous: Credentials) {
+        self.undo_stack.push(HistoryEntry {
+            description,
+            credentials: previous,
+        });
+        if self.undo_stack.len() > MAX_HISTORY_SIZE {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    /// Reverts the last operation. Returns its description and the credentials to restore.
+    pub fn undo(&mut self, current: &Credentials) -> Option<(String, Credentials)> {
+        let mut entry = self.undo_stack.pop()?;
+        entry.credentials.keep_usage_from(current);
+        self.redo_stack.push(HistoryEntry {
+            description: entry.description.clone(),
+            credentials: current.clone(),
+        });
+        Some((entry.description, entry.credentials))
+    }
+
+    /// Applies the last undone operation again.
+    pub fn redo(&mut self, current: &Credentials) -> Option<(String, Credentials)> {
+        let mut entry = self.redo_stack.pop()?;
+        entry.credentials.keep_usage_from(current);
+        self.undo_stack.push(HistoryEntry {
+            description: entry.description.clone(),
+            credentials: current.clone(),
+        });
+        Some((entry.description, entry.credentials))
+    }
+
+    pub fn get_undo_description(&self) -> Option<&str> {
+        self.undo_stack
+            .last()
+            .map(|entry| entry.description.as_str())
+    }
+
+    pub fn get_redo_description(&self) -> Option<&str> {
+        self.redo_stack
+            .last()
+            .map(|entry| entry.description.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::models::credentials::Credential;
+    use crate::app::models::otp_params::{OtpAlgorithmEnum, OtpKindEnum, OtpParams};
+
+    fn credential(website: &str) -> Credential {
+        Credential::new(
+            Some(website.to_string()),
+            Some("me@example.com".to_string()),
+            None,
+            Some("hunter2".to_string()),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history = History::new();
+        let mut credentials = Credentials::new();
+
+        history.record("add a".to_string(), credentials.clone());
+        credentials.add_or_update_credential(credential("a"));
+        history.record("add b".to_string(), credentials.clone());
+        credentials.add_or_update_credential(credential("b"));
+        assert_eq!(history.get_undo_description(), Some("add b"));
+
+        let (description, previous) = history.undo(&credentials).unwrap();
+        assert_eq!(description, "add b");
+        credentials = previous;
+        assert_eq!(credentials.get_websites(), vec!["a"]);
+        assert_eq!(history.get_redo_description(), Some("add b"));
+
+        let (_, next) = history.redo(&credentials).unwrap();
+        credentials = next;
+        assert_eq!(credentials.get_websites().len(), 2);
+
+        history.undo(&credentials).unwrap();
+        history.record("add c".to_string(), credentials.clone());
+        assert!(history.get_redo_description().is_none());
+        assert_eq!(history.get_undo_description(), Some("add c"));
+    }
+
+    #[test]
+    fn test_undo_keeps_hotp_counter() {
+        let mut history = History::new();
+        let mut credentials = Credentials::new();
+        let mut hotp = credential("a");
+        hotp.otp = Some(OtpParams::new(
+            OtpKindEnum::Hotp,
+            b"12345678901234567890".to_vec(),
+            OtpAlgorithmEnum::Sha1,
+            6,
+            30,
+            3,
+        ));
+        credentials.add_or_update_credential(hotp);
+
+        history.record("add b".to_string(), credentials.clone());
+        credentials.add_or_update_credential(credential("b"));
+        let mut otp = credentials.credentials[0].otp.clone().unwrap();
+        otp.increment_counter();
+        credentials.set_otp("a", "me@example.com", Some(otp));
+        credentials.mark_accessed("a", "me@example.com");
+
+        let (_, previous) = history.undo(&credentials).unwrap();
+        assert_eq!(previous.credentials.len(), 1);
+        let restored = &previous.credentials[0];
+        assert_eq!(restored.otp.as_ref().unwrap().get_counter(), 4);
+        assert_eq!(
+            restored.last_accessed_at,
+            credentials.credentials[0].last_accessed_at
+        );
+
+        let (_, next) = history.redo(&previous).unwrap();
+        assert_eq!(next.credentials[0].otp.as_ref().unwrap().get_counter(), 4);
+    }
+}
@@ -0,0 +1,234 @@
+use data_encoding::HEXLOWER;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use super::credentials_storage;
+use super::models::vault_security_params::KdfParamsEnum;
+
+// Only the session keyring possessor can view, read, write, search, link and change the key.
+#[cfg(target_os = "linux")]
+const KEY_PERMISSIONS: u32 = 0x3f00_0000;
+
+#[derive(Debug)]
+pub struct KeyringError(String);
+
+impl fmt::Display for KeyringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Kernel keyring: {}", self.0)
+    }
+}
+
+impl Error for KeyringError {}
+
+/// Names the key of a vault after its path and its key derivation salt, so that vaults in
+/// different directories, or a vault created again in the same place, never share a key.
+pub fn get_key_description(vault_path: &Path, salt: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(vault_path.as_os_str().as_encoded_bytes());
+    hasher.update([0]);
+    hasher.update(salt);
+    format!("keylox:{}", &HEXLOWER.encode(&hasher.finalize())[..32])
+}
+
+/// Returns the description of the key of the vault in the current directory, or None when
+/// there is no vault.
+pub fn get_vault_key_description(kdf_params: &KdfParamsEnum) -> Option<String> {
+    let vault_path = credentials_storage::get_vault_path().ok()?;
+    let salt = match kdf_params {
+        KdfParamsEnum::Argon2(argon2_params) => argon2_params.get_salt(),
+        KdfParamsEnum::Pbkdf2(pbkdf2_params) => pbkdf2_params.get_salt(),
+    };
+    Some(get_key_description(&vault_path, &salt))
+}
+
+#[cfg(target_os = "linux")]
+mod syscalls {
+    use super::KeyringError;
+    use std::ffi::CString;
+    use std::io;
+
+    pub fn to_c_string(value: &str) -> CString {
+        CString::new(value).expect("key descriptions have no NUL bytes")
+    }
+
+    /// Turns the -1 of a failed system call into the error in errno.
+    pub fn check(result: libc::c_long) -> Result<libc::c_long, KeyringError> {
+        if result == -1 {
+            return Err(KeyringError(io::Error::last_os_error().to_string()));
+        }
+        Ok(result)
+    }
+
+    /// Returns the session keyring to add keys to. A process without one of its own, when the
+    /// login does not go through pam_keyinit, would get a new keyring that dies with it, so the
+    /// user session keyring, which all sessions of the user share, is used instead. Searching
+    /// the session keyring falls back to it as well.
+    pub fn get_target_keyring() -> Result<libc::c_long, KeyringError> {
+        // SAFETY: keyctl with integer arguments only, which does not create a keyring.
+        let get_id = |keyring: libc::c_int| {
+            check(unsafe {
+                libc::syscall(libc::SYS_keyctl, libc::KEYCTL_GET_KEYRING_ID, keyring, 0)
+            })
+        };
+        if get_id(libc::KEY_SPEC_SESSION_KEYRING)? == get_id(libc::KEY_SPEC_USER_SESSION_KEYRING)? {
+            return Ok(libc::KEY_SPEC_USER_SESSION_KEYRING as libc::c_long);
+        }
+        Ok(libc::KEY_SPEC_SESSION_KEYRING as libc::c_long)
+    }
+
+    /// Returns the serial of the key in the session keyring, or None if there is none.
+    pub fn search(description: &str) -> Result<Option<libc::c_long>, KeyringError> {
+        let key_type = to_c_string("user");
+        let description = to_c_string(description);
+        // SAFETY: both strings are NUL-terminated and outlive the call.
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_keyctl,
+                libc::KEYCTL_SEARCH,
+                libc::KEY_SPEC_SESSION_KEYRING,
+                key_type.as_ptr(),
+                description.as_ptr(),
+                0,
+            )
+        };
+        if result == -1 {
+            let err = io::Error::last_os_error();
+            // ENOKEY when there is no such key, EKEYEXPIRED and EKEYREVOKED once it is gone.
+            if matches!(
+                err.raw_os_error(),
+                Some(libc::ENOKEY | libc::EKEYEXPIRED | libc::EKEYREVOKED)
+            ) {
+                return Ok(None);
+            }
+            return Err(KeyringError(err.to_string()));
+        }
+        Ok(Some(result))
+    }
+}
+
+/// Stores the key in the session keyring, replacing the one stored before. The kernel drops it
+/// after `timeout_seconds`, even when KeyLox never runs again.
+#[cfg(target_os = "linux")]
+pub fn store_key(description: &str, key: &[u8], timeout_seconds: u32) -> Result<(), KeyringError> {
+    let keyring = syscalls::get_target_keyring()?;
+    let key_type = syscalls::to_c_string("user");
+    let c_description = syscalls::to_c_string(description);
+    // SAFETY: the strings are NUL-terminated and the payload pointer and length describe `key`.
+    let serial = syscalls::check(unsafe {
+        libc::syscall(
+            libc::SYS_add_key,
+            key_type.as_ptr(),
+            c_description.as_ptr(),
+            key.as_ptr(),
+            key.len(),
+            keyring,
+        )
+    })?;
+
+    // SAFETY: keyctl with integer arguments only.
+    let result = syscalls::check(unsafe {
+        libc::syscall(
+            libc::SYS_keyctl,
+            libc::KEYCTL_SET_TIMEOUT,
+            serial,
+            timeout_seconds,
+        )
+    })
+    .and_then(|_| {
+        // SAFETY: as above.
+        syscalls::check(unsafe {
+            libc::syscall(
+                libc::SYS_keyctl,
+                libc::KEYCTL_SETPERM,
+                serial,
+                KEY_PERMISSIONS,
+            )
+        })
+    });
+    if let Err(err) = result {
+        // A key that cannot expire is not kept.
+        revoke_key(description)?;
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Returns the key stored for the description, unless it expired or was revoked.
+#[cfg(target_os = "linux")]
+pub fn get_key(description: &str) -> Result<Option<Vec<u8>>, KeyringError> {
+    let serial = match syscalls::search(description)? {
+        Some(serial) => serial,
+        None => return Ok(None),
+    };
+
+    let mut key = vec![0u8; 64];
+    // SAFETY: the buffer pointer and length describe `key`.
+    let size = syscalls::check(unsafe {
+        libc::syscall(
+            libc::SYS_keyctl,
+            libc::KEYCTL_READ,
+            serial,
+            key.as_mut_ptr(),
+            key.len(),
+        )
+    })? as usize;
+    if size > key.len() {
+        return Err(KeyringError(format!("unexpected key size {}", size)));
+    }
+    key.truncate(size);
+    Ok(Some(key))
+}
+
+/// Revokes the key at once. Returns whether there was one.
+#[cfg(target_os = "linux")]
+pub fn revoke_key(description: &str) -> Result<bool, KeyringError> {
+    let serial = match syscalls::search(description)? {
+        Some(serial) => serial,
+        None => return Ok(false),
+    };
+    // SAFETY: keyctl with integer arguments only.
+    syscalls::check(unsafe { libc::syscall(libc::SYS_keyctl, libc::KEYCTL_REVOKE, serial) })?;
+    Ok(true)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn store_key(
+    _description: &str,
+    _key: &[u8],
+    _timeout_seconds: u32,
+) -> Result<(), KeyringError> {
+    Err(KeyringError("only available on Linux".to_string()))
+}
+
+// Nothing can be cached elsewhere, so there is nothing to find or revoke.
+#[cfg(not(target_os = "linux"))]
+pub fn get_key(_description: &str) -> Result<Option<Vec<u8>>, KeyringError> {
+    Ok(None)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn revoke_key(_description: &str) -> Result<bool, KeyringError> {
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_description() {
+        let description = get_key_description(Path::new("/home/me/credentials.json"), &[1; 32]);
+        assert!(description.starts_with("keylox:"));
+        assert_eq!(description.len(), "keylox:".len() + 32);
+        assert_ne!(
+            description,
+            get_key_description(Path::new("/home/me/credentials.json"), &[2; 32])
+        );
+        assert_ne!(
+            description,
+            get_key_description(Path::new("/srv/credentials.json"), &[1; 32])
+        );
+    }
+}
@@ -1,6 +1,24 @@
 pub(super) mod app;
+pub(super) mod attachments;
+pub(super) mod breach_check;
+pub(super) mod clipboard;
+pub(super) mod config;
+pub(super) mod confirmation;
 pub(super) mod credentials_storage;
 pub(super) mod encryption;
+pub(super) mod external_editor;
+pub(super) mod health;
+pub(super) mod history;
+pub(super) mod keyring;
 pub(super) mod models;
+pub(super) mod otp;
+pub(super) mod otp_import;
+pub(super) mod password_generator;
+pub(super) mod password_rules;
+pub(super) mod password_strength;
 pub(super) mod pbkdf;
+pub(super) mod search;
+pub(super) mod text_area;
+pub(super) mod text_input;
 pub(super) mod vault_encryptor;
+pub(super) mod wordlist;
@@ -0,0 +1,159 @@
+use chrono::{DateTime, Utc};
+use data_encoding::BASE64;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+use super::vault_security_params::EncryptionParamsEnum;
+
+/// A file attached to a credential. Only the metadata and the content key live in the
+/// credentials; the content is an `EncryptedAttachment` stored next to them in the vault.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Attachment {
+    id: String,
+    file_name: String,
+    size: u64,
+    key: Vec<u8>,
+    added_at: DateTime<Utc>,
+}
+
+/// The content of an attachment, encrypted once with its own key so that saving the vault
+/// does not re-encrypt it.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct EncryptedAttachment {
+    id: String,
+    encryption_params: EncryptionParamsEnum,
+    #[serde(
+        serialize_with = "serialize_data",
+        deserialize_with = "deserialize_data"
+    )]
+    data: Vec<u8>,
+}
+
+impl Attachment {
+    pub fn new(id: String, file_name: String, size: u64, key: Vec<u8>) -> Self {
+        Attachment {
+            id,
+            file_name,
+            size,
+            key,
+            added_at: Utc::now(),
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_file_name(&self) -> String {
+        self.file_name.clone()
+    }
+
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn get_key(&self) -> Vec<u8> {
+        self.key.clone()
+    }
+}
+
+impl EncryptedAttachment {
+    pub fn new(id: String, encryption_params: EncryptionParamsEnum, data: Vec<u8>) -> Self {
+        EncryptedAttachment {
+            id,
+            encryption_params,
+            data,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn get_encryption_params(&self) -> EncryptionParamsEnum {
+        self.encryption_params.clone()
+    }
+
+    pub fn get_data(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+/// Writes the content as base64 in JSON, where a list of numbers would take about ten times
+/// the size, and as raw bytes in binary formats.
+fn serialize_data<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&BASE64.encode(data))
+    } else {
+        serializer.serialize_bytes(data)
+    }
+}
+
+/// Reads the content as written by `serialize_data`, or as the list of numbers of vaults
+/// saved before.
+fn deserialize_data<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    struct DataVisitor;
+
+    impl<'de> de::Visitor<'de> for DataVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "base64, bytes or a list of bytes")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Vec<u8>, E> {
+            BASE64
+                .decode(value.as_bytes())
+                .map_err(|_| E::custom("attachment data is not valid base64"))
+        }
+
+        fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(value.to_vec())
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut data = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                data.push(byte);
+            }
+            Ok(data)
+        }
+    }
+
+    deserializer.deserialize_any(DataVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::models::vault_security_params::AegisParams;
+
+    #[test]
+    fn test_data_serialization() {
+        let attachment = EncryptedAttachment::new(
+            "id".to_string(),
+            EncryptionParamsEnum::Aegis(AegisParams::new(32, vec![1; 32], vec![2; 32])),
+            vec![0, 1, 254, 255],
+        );
+
+        let json = serde_json::to_string(&attachment).unwrap();
+        assert!(json.contains("\"data\":\"AAH+/w==\""));
+        assert_eq!(
+            serde_json::from_str::<EncryptedAttachment>(&json).unwrap(),
+            attachment
+        );
+
+        let msgpack = rmp_serde::to_vec(&attachment).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<EncryptedAttachment>(&msgpack).unwrap(),
+            attachment
+        );
+
+        // Vaults saved before wrote the content as a list of numbers.
+        let old_json = json.replace("\"AAH+/w==\"", "[0,1,254,255]");
+        assert_eq!(
+            serde_json::from_str::<EncryptedAttachment>(&old_json).unwrap(),
+            attachment
+        );
+    }
+}
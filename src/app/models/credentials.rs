@@ -1,48 +1,322 @@
+use chrono::{DateTime, Duration, Utc};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    attachment::Attachment,
+    otp_params::OtpParams,
+    rotation_policy::{RotationPolicyEnum, RotationStatusEnum},
+    vault_settings::VaultSettings,
+};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Credentials {
     pub credentials: Vec<Credential>,
+    #[serde(default)]
+    pub trash: Vec<TrashedCredential>,
+    #[serde(default)]
+    pub settings: VaultSettings,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct Credential {
     pub website: String,
     pub email: String,
     pub username: String,
     pub password: String,
     pub notes: String,
+    // Timestamps are optional so that vaults written before they existed still load.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub modified_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub password_modified_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub otp: Option<OtpParams>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub rotation: Option<RotationPolicyEnum>,
+    // The site's password rules in Apple's passwordrules syntax, used by the generator.
+    #[serde(default)]
+    pub password_rules: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashedCredential {
+    pub credential: Credential,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// What differs from an earlier copy of the credentials, such as the one last saved. Entries
+/// are identified by website and email.
+#[derive(Default, PartialEq, Debug)]
+pub struct CredentialChanges {
+    pub added: Vec<(String, String)>,
+    pub modified: Vec<(String, String)>,
+    pub removed: Vec<(String, String)>,
+    pub is_trash_changed: bool,
+    pub are_settings_changed: bool,
+}
+
+impl CredentialChanges {
+    pub fn is_empty(&self) -> bool {
+        *self == CredentialChanges::default()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CredentialSortOrder {
+    Website,
+    Created,
+    Modified,
+    LastAccessed,
 }
 
 impl Credentials {
     pub fn new() -> Self {
         Credentials {
             credentials: Vec::new(),
+            trash: Vec::new(),
+            settings: VaultSettings::default(),
         }
     }
 
     pub fn add_or_update_credential(&mut self, credential: Credential) {
+        let now = Utc::now();
         let website = credential.website.clone();
         let email = credential.email.clone();
         let mut found = false;
         for c in &mut self.credentials {
             if c.website == website && c.email == email {
+                if c.password != credential.password {
+                    c.password_modified_at = Some(now);
+                }
+                if c.username != credential.username
+                    || c.password != credential.password
+                    || c.notes != credential.notes
+                    || c.otp != credential.otp
+                    || c.attachments != credential.attachments
+                    || c.tags != credential.tags
+                    || c.rotation != credential.rotation
+                    || c.password_rules != credential.password_rules
+                {
+                    c.modified_at = Some(now);
+                }
+
                 c.username = credential.username.clone();
                 c.password = credential.password.clone();
                 c.notes = credential.notes.clone();
+                c.otp = credential.otp.clone();
+                c.attachments = credential.attachments.clone();
+                c.tags = credential.tags.clone();
+                c.rotation = credential.rotation.clone();
+                c.password_rules = credential.password_rules.clone();
                 found = true;
                 break;
             }
         }
         if !found {
+            let mut credential = credential;
+            credential.created_at = Some(now);
+            credential.modified_at = Some(now);
+            credential.password_modified_at = Some(now);
             self.credentials.push(credential);
         }
     }
 
-    pub fn remove_credential(&mut self, website: &str, email: &str) {
+    pub fn remove_credential(&mut self, website: &str, email: &str) -> Option<Credential> {
+        let index = self
+            .credentials
+            .iter()
+            .position(|c| c.website == website && c.email == email)?;
+        Some(self.credentials.remove(index))
+    }
+
+    pub fn move_to_trash(&mut self, website: &str, email: &str) -> bool {
+        match self.remove_credential(website, email) {
+            Some(credential) => {
+                self.trash.push(TrashedCredential {
+                    credential,
+                    deleted_at: Utc::now(),
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves a trashed credential back. Fails if a credential with the same website and
+    /// email was created in the meantime.
+    pub fn restore_from_trash(&mut self, index: usize) -> Result<(), String> {
+        let trashed = self
+            .trash
+            .get(index)
+            .ok_or("No trashed credential selected")?;
+        let website = &trashed.credential.website;
+        let email = &trashed.credential.email;
+
+        if self.get_credential(website, email).is_some() {
+            return Err(format!(
+                "{} / {} already exists, remove it before restoring",
+                website, email
+            ));
+        }
+
+        let trashed = self.trash.remove(index);
+        self.credentials.push(trashed.credential);
+        Ok(())
+    }
+
+    pub fn purge_from_trash(&mut self, index: usize) -> Option<TrashedCredential> {
+        if index < self.trash.len() {
+            Some(self.trash.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Permanently removes credentials that have been in the trash longer than the
+    /// retention configured in the settings. Returns how many were removed.
+    pub fn empty_expired_trash(&mut self) -> usize {
+        if self.settings.trash_retention_days == 0 {
+            return 0;
+        }
+
+        let cutoff = Utc::now() - Duration::days(self.settings.trash_retention_days as i64);
+        let trash_size = self.trash.len();
+        self.trash.retain(|t| t.deleted_at > cutoff);
+        trash_size - self.trash.len()
+    }
+
+    /// Returns what differs from `other`. When a credential was last used does not count as a
+    /// change, since merely opening one updates it.
+    pub fn get_changes_from(&self, other: &Credentials) -> CredentialChanges {
+        let mut changes = CredentialChanges {
+            is_trash_changed: self.trash != other.trash,
+            are_settings_changed: self.settings != other.settings,
+            ..CredentialChanges::default()
+        };
+        // Most checks find nothing changed, and the credentials in the same order.
+        if self.credentials.len() == other.credentials.len()
+            && self
+                .credentials
+                .iter()
+                .zip(&other.credentials)
+                .all(|(a, b)| a.has_same_content(b))
+        {
+            return changes;
+        }
+
+        let previous: HashMap<(&str, &str), &Credential> = other
+            .credentials
+            .iter()
+            .map(|c| ((c.website.as_str(), c.email.as_str()), c))
+            .collect();
+        let current: HashSet<(&str, &str)> = self
+            .credentials
+            .iter()
+            .map(|c| (c.website.as_str(), c.email.as_str()))
+            .collect();
+
+        for c in &self.credentials {
+            let key = (c.website.clone(), c.email.clone());
+            match previous.get(&(c.website.as_str(), c.email.as_str())) {
+                None => changes.added.push(key),
+                Some(previous) if !c.has_same_content(previous) => changes.modified.push(key),
+                Some(_) => {}
+            }
+        }
+        for c in &other.credentials {
+            if !current.contains(&(c.website.as_str(), c.email.as_str())) {
+                changes.removed.push((c.website.clone(), c.email.clone()));
+            }
+        }
+        changes
+    }
+
+    /// Returns whether any credential was viewed or copied since `other`, which
+    /// `get_changes_from` leaves out.
+    pub fn is_accessed_since(&self, other: &Credentials) -> bool {
+        let accessed_at: HashMap<(&str, &str), Option<DateTime<Utc>>> = other
+            .credentials
+            .iter()
+            .map(|c| ((c.website.as_str(), c.email.as_str()), c.last_accessed_at))
+            .collect();
+        self.credentials.iter().any(|c| {
+            accessed_at
+                .get(&(c.website.as_str(), c.email.as_str()))
+                .is_some_and(|accessed_at| *accessed_at != c.last_accessed_at)
+        })
+    }
+
+    /// Records that the credential was viewed or copied.
+    pub fn mark_accessed(&mut self, website: &str, email: &str) {
+        if let Some(c) = self
+            .credentials
+            .iter_mut()
+            .find(|c| c.website == website && c.email == email)
+        {
+            c.last_accessed_at = Some(Utc::now());
+        }
+    }
+
+    /// Brings the HOTP counters and access times forward to those of `current`. Restoring an
+    /// earlier copy of the credentials then never shows a one-time code that was already used.
+    pub fn keep_usage_from(&mut self, current: &Credentials) {
+        let current: HashMap<(&str, &str), &Credential> = current
+            .credentials
+            .iter()
+            .chain(current.trash.iter().map(|t| &t.credential))
+            .map(|c| ((c.website.as_str(), c.email.as_str()), c))
+            .collect();
+
+        for c in self
+            .credentials
+            .iter_mut()
+            .chain(self.trash.iter_mut().map(|t| &mut t.credential))
+        {
+            let current = match current.get(&(c.website.as_str(), c.email.as_str())) {
+                Some(current) => current,
+                None => continue,
+            };
+            if let (Some(otp), Some(current_otp)) = (c.otp.as_mut(), current.otp.as_ref()) {
+                if otp.is_counter_based()
+                    && current_otp.is_counter_based()
+                    && otp.get_secret() == current_otp.get_secret()
+                    && otp.get_counter() < current_otp.get_counter()
+                {
+                    otp.set_counter(current_otp.get_counter());
+                }
+            }
+            c.last_accessed_at = c.last_accessed_at.max(current.last_accessed_at);
+        }
+    }
+
+    pub fn set_otp(&mut self, website: &str, email: &str, otp: Option<OtpParams>) {
+        if let Some(c) = self
+            .credentials
+            .iter_mut()
+            .find(|c| c.website == website && c.email == email)
+        {
+            c.otp = otp;
+        }
+    }
+
+    /// Returns the ids of all attachments still referenced by a credential, including
+    /// trashed ones.
+    pub fn get_attachment_ids(&self) -> HashSet<String> {
         self.credentials
-            .retain(|c| c.website != website || c.email != email);
+            .iter()
+            .chain(self.trash.iter().map(|t| &t.credential))
+            .flat_map(|c| c.attachments.iter().map(|a| a.get_id()))
+            .collect()
     }
 
     pub fn get_websites(&self) -> Vec<String> {
@@ -53,6 +327,72 @@ impl Credentials {
             .collect()
     }
 
+    /// Returns the unique websites ordered by `sort_order`, each with the newest timestamp of
+    /// the kind selected by `sort_order` among its credentials. Timestamp orders put the most
+    /// recent first. The timestamps are None when sorting by website.
+    pub fn get_websites_sorted(
+        &self,
+        sort_order: CredentialSortOrder,
+    ) -> Vec<(String, Option<DateTime<Utc>>)> {
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        let mut websites: Vec<(String, Option<DateTime<Utc>>)> = Vec::new();
+        for c in &self.credentials {
+            let timestamp = c.get_timestamp(sort_order);
+            match indices.get(c.website.as_str()) {
+                Some(&i) => websites[i].1 = websites[i].1.max(timestamp),
+                None => {
+                    indices.insert(&c.website, websites.len());
+                    websites.push((c.website.clone(), timestamp));
+                }
+            }
+        }
+
+        match sort_order {
+            CredentialSortOrder::Website => {
+                websites.sort_by_cached_key(|(w, _)| w.to_lowercase());
+            }
+            _ => {
+                websites.sort_by_cached_key(|(w, timestamp)| {
+                    (std::cmp::Reverse(*timestamp), w.to_lowercase())
+                });
+            }
+        }
+
+        websites
+    }
+
+    /// Returns the most urgent rotation status among the credentials of each website.
+    pub fn get_website_rotation_statuses(&self) -> HashMap<&str, RotationStatusEnum> {
+        let now = Utc::now();
+        let mut statuses: HashMap<&str, RotationStatusEnum> = HashMap::new();
+        for c in &self.credentials {
+            let status = c.get_rotation_status(&self.settings, now);
+            let website_status = statuses
+                .entry(c.website.as_str())
+                .or_insert(RotationStatusEnum::Ok);
+            *website_status = (*website_status).max(status);
+        }
+        statuses
+    }
+
+    /// Returns the credentials that are due or overdue for rotation, most urgent first.
+    pub fn get_due_for_rotation(&self) -> Vec<(Credential, DateTime<Utc>)> {
+        let now = Utc::now();
+        let mut due: Vec<(Credential, DateTime<Utc>)> = self
+            .credentials
+            .iter()
+            .filter_map(|c| {
+                let due_date = c.get_rotation_due_date(&self.settings)?;
+                match RotationStatusEnum::from_due_date(Some(due_date), now) {
+                    RotationStatusEnum::Ok => None,
+                    _ => Some((c.clone(), due_date)),
+                }
+            })
+            .collect();
+        due.sort_by_key(|(c, due_date)| (*due_date, c.website.to_lowercase()));
+        due
+    }
+
     pub fn get_emails(&self, website: &str) -> Vec<String> {
         self.credentials
             .iter()
@@ -85,6 +425,316 @@ impl Credential {
             username: username.unwrap_or_else(|| String::new()),
             password: password.unwrap_or_else(|| String::new()),
             notes: notes.unwrap_or_else(|| String::new()),
+            created_at: None,
+            modified_at: None,
+            password_modified_at: None,
+            last_accessed_at: None,
+            otp: None,
+            attachments: Vec::new(),
+            tags: Vec::new(),
+            rotation: None,
+            password_rules: String::new(),
+        }
+    }
+
+    /// Returns the policy of the credential, or else the shortest default interval among
+    /// its tags.
+    pub fn get_rotation_policy(&self, settings: &VaultSettings) -> Option<RotationPolicyEnum> {
+        if self.rotation.is_some() {
+            return self.rotation.clone();
+        }
+
+        self.tags
+            .iter()
+            .filter_map(|tag| settings.rotation_defaults.get(&tag.to_lowercase()))
+            .min()
+            .map(|days| RotationPolicyEnum::EveryDays(*days))
+    }
+
+    pub fn get_rotation_due_date(&self, settings: &VaultSettings) -> Option<DateTime<Utc>> {
+        self.get_rotation_policy(settings)?
+            .get_due_date(self.password_modified_at.or(self.created_at))
+    }
+
+    pub fn get_rotation_status(
+        &self,
+        settings: &VaultSettings,
+        now: DateTime<Utc>,
+    ) -> RotationStatusEnum {
+        RotationStatusEnum::from_due_date(self.get_rotation_due_date(settings), now)
+    }
+
+    /// Returns whether the credentials are the same but for when they were last used.
+    fn has_same_content(&self, other: &Credential) -> bool {
+        // Listing every field makes a new one fail to compile here until it is compared.
+        let Credential {
+            website,
+            email,
+            username,
+            password,
+            notes,
+            created_at,
+            modified_at,
+            password_modified_at,
+            last_accessed_at: _,
+            otp,
+            attachments,
+            tags,
+            rotation,
+            password_rules,
+        } = self;
+        *website == other.website
+            && *email == other.email
+            && *username == other.username
+            && *password == other.password
+            && *notes == other.notes
+            && *created_at == other.created_at
+            && *modified_at == other.modified_at
+            && *password_modified_at == other.password_modified_at
+            && *otp == other.otp
+            && *attachments == other.attachments
+            && *tags == other.tags
+            && *rotation == other.rotation
+            && *password_rules == other.password_rules
+    }
+
+    pub fn get_timestamp(&self, sort_order: CredentialSortOrder) -> Option<DateTime<Utc>> {
+        match sort_order {
+            CredentialSortOrder::Website => None,
+            CredentialSortOrder::Created => self.created_at,
+            CredentialSortOrder::Modified => self.modified_at,
+            CredentialSortOrder::LastAccessed => self.last_accessed_at,
+        }
+    }
+}
+
+impl CredentialSortOrder {
+    pub fn next(&self) -> Self {
+        match self {
+            CredentialSortOrder::Website => CredentialSortOrder::Created,
+            CredentialSortOrder::Created => CredentialSortOrder::Modified,
+            CredentialSortOrder::Modified => CredentialSortOrder::LastAccessed,
+            CredentialSortOrder::LastAccessed => CredentialSortOrder::Website,
+        }
+    }
+
+    pub fn is_by_timestamp(&self) -> bool {
+        *self != CredentialSortOrder::Website
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            CredentialSortOrder::Website => "website",
+            CredentialSortOrder::Created => "created",
+            CredentialSortOrder::Modified => "modified",
+            CredentialSortOrder::LastAccessed => "last used",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_sets_timestamps() {
+        let mut credentials = Credentials::new();
+        credentials.add_or_update_credential(Credential::new(
+            Some("example.com".to_string()),
+            Some("a@example.com".to_string()),
+            None,
+            Some("hunter2".to_string()),
+            None,
+        ));
+
+        let credential = credentials
+            .get_credential("example.com", "a@example.com")
+            .unwrap();
+        assert!(credential.created_at.is_some());
+        assert_eq!(credential.created_at, credential.modified_at);
+        assert_eq!(credential.created_at, credential.password_modified_at);
+        assert!(credential.last_accessed_at.is_none());
+    }
+
+    #[test]
+    fn test_update_keeps_created_and_tracks_password_change() {
+        let mut credentials = Credentials::new();
+        let credential = Credential::new(
+            Some("example.com".to_string()),
+            Some("a@example.com".to_string()),
+            None,
+            Some("hunter2".to_string()),
+            None,
+        );
+        credentials.add_or_update_credential(credential.clone());
+        let created_at = credentials.credentials[0].created_at;
+
+        let mut updated = credential.clone();
+        updated.notes = "new notes".to_string();
+        credentials.add_or_update_credential(updated);
+
+        let stored = &credentials.credentials[0];
+        assert_eq!(stored.created_at, created_at);
+        assert!(stored.modified_at >= created_at);
+        assert_eq!(stored.password_modified_at, created_at);
+    }
+
+    #[test]
+    fn test_deserialize_credential_without_timestamps() {
+        #[derive(Serialize)]
+        struct LegacyCredential {
+            website: String,
+            email: String,
+            username: String,
+            password: String,
+            notes: String,
+        }
+
+        let legacy = LegacyCredential {
+            website: "example.com".to_string(),
+            email: "a@example.com".to_string(),
+            username: "a".to_string(),
+            password: "hunter2".to_string(),
+            notes: String::new(),
+        };
+
+        let serialized = rmp_serde::to_vec(&legacy).unwrap();
+        let credential: Credential = rmp_serde::from_slice(&serialized).unwrap();
+
+        assert_eq!(credential.website, "example.com");
+        assert!(credential.created_at.is_none());
+        assert!(credential.last_accessed_at.is_none());
+    }
+
+    #[test]
+    fn test_trash_restore_and_expiry() {
+        let mut credentials = Credentials::new();
+        let credential = Credential::new(
+            Some("example.com".to_string()),
+            Some("a@example.com".to_string()),
+            None,
+            Some("hunter2".to_string()),
+            None,
+        );
+        credentials.add_or_update_credential(credential.clone());
+
+        assert!(credentials.move_to_trash("example.com", "a@example.com"));
+        assert!(credentials.credentials.is_empty());
+        assert_eq!(credentials.trash.len(), 1);
+
+        // Restoring is refused while a credential with the same key exists.
+        credentials.add_or_update_credential(credential);
+        assert!(credentials.restore_from_trash(0).is_err());
+        credentials.remove_credential("example.com", "a@example.com");
+        assert!(credentials.restore_from_trash(0).is_ok());
+        assert!(credentials.trash.is_empty());
+
+        credentials.move_to_trash("example.com", "a@example.com");
+        credentials.trash[0].deleted_at = Utc::now() - Duration::days(31);
+        assert_eq!(credentials.empty_expired_trash(), 1);
+        assert!(credentials.trash.is_empty());
+    }
+
+    #[test]
+    fn test_websites_sorted() {
+        let mut credentials = Credentials::new();
+        for (website, email) in [
+            ("b.example", "me@example.com"),
+            ("A.example", "me@example.com"),
+            ("b.example", "other@example.com"),
+        ] {
+            credentials.add_or_update_credential(Credential::new(
+                Some(website.to_string()),
+                Some(email.to_string()),
+                None,
+                None,
+                None,
+            ));
+        }
+        credentials.mark_accessed("b.example", "other@example.com");
+
+        let websites = credentials.get_websites_sorted(CredentialSortOrder::Website);
+        assert_eq!(websites[0], ("A.example".to_string(), None));
+        assert_eq!(websites[1], ("b.example".to_string(), None));
+
+        let websites = credentials.get_websites_sorted(CredentialSortOrder::LastAccessed);
+        assert_eq!(websites[0].0, "b.example");
+        assert_eq!(websites[0].1, credentials.credentials[2].last_accessed_at);
+        assert_eq!(websites[1], ("A.example".to_string(), None));
+    }
+
+    #[test]
+    fn test_changes() {
+        let mut credentials = Credentials::new();
+        for website in ["a.example", "b.example", "c.example"] {
+            credentials.add_or_update_credential(Credential::new(
+                Some(website.to_string()),
+                Some("me@example.com".to_string()),
+                None,
+                Some("hunter2".to_string()),
+                None,
+            ));
         }
+        let saved = credentials.clone();
+
+        // Opening a credential is not a change, but is still saved.
+        assert!(!credentials.is_accessed_since(&saved));
+        credentials.mark_accessed("a.example", "me@example.com");
+        assert_eq!(
+            credentials.get_changes_from(&saved),
+            CredentialChanges::default()
+        );
+        assert!(credentials.is_accessed_since(&saved));
+
+        credentials.credentials[0].password = "correct horse".to_string();
+        credentials.move_to_trash("b.example", "me@example.com");
+        credentials.add_or_update_credential(Credential::new(
+            Some("d.example".to_string()),
+            Some("me@example.com".to_string()),
+            None,
+            None,
+            None,
+        ));
+        let key = |website: &str| (website.to_string(), "me@example.com".to_string());
+        assert_eq!(
+            credentials.get_changes_from(&saved),
+            CredentialChanges {
+                added: vec![key("d.example")],
+                modified: vec![key("a.example")],
+                removed: vec![key("b.example")],
+                is_trash_changed: true,
+                are_settings_changed: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_rotation_defaults_by_tag() {
+        let mut credentials = Credentials::new();
+        credentials
+            .settings
+            .rotation_defaults
+            .insert("bank".to_string(), 90);
+
+        let mut credential = Credential::new(
+            Some("bank.example".to_string()),
+            Some("a@example.com".to_string()),
+            None,
+            Some("hunter2".to_string()),
+            None,
+        );
+        credential.tags = vec!["Bank".to_string()];
+        credentials.add_or_update_credential(credential);
+        assert!(credentials.get_due_for_rotation().is_empty());
+
+        credentials.credentials[0].password_modified_at = Some(Utc::now() - Duration::days(91));
+        assert_eq!(
+            credentials.get_website_rotation_statuses()["bank.example"],
+            RotationStatusEnum::Overdue
+        );
+
+        // A policy on the credential takes precedence over the tag default.
+        credentials.credentials[0].rotation = Some(RotationPolicyEnum::EveryDays(365));
+        assert!(credentials.get_due_for_rotation().is_empty());
     }
 }
@@ -1,3 +1,8 @@
+pub(super) mod attachment;
 pub(super) mod credentials;
+pub(super) mod otp_params;
+pub(super) mod password_generator_settings;
+pub(super) mod rotation_policy;
 pub(super) mod vault;
 pub(super) mod vault_security_params;
+pub(super) mod vault_settings;
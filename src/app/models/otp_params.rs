@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_TOTP_PERIOD: u64 = 30;
+pub const DEFAULT_OTP_DIGITS: u32 = 6;
+pub const STEAM_GUARD_DIGITS: u32 = 5;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum OtpKindEnum {
+    Totp,
+    Hotp,
+    Steam,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum OtpAlgorithmEnum {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct OtpParams {
+    kind: OtpKindEnum,
+    secret: Vec<u8>,
+    algorithm: OtpAlgorithmEnum,
+    digits: u32,
+    period: u64,
+    counter: u64,
+    issuer: String,
+    account_name: String,
+}
+
+impl OtpParams {
+    pub fn new(
+        kind: OtpKindEnum,
+        secret: Vec<u8>,
+        algorithm: OtpAlgorithmEnum,
+        digits: u32,
+        period: u64,
+        counter: u64,
+    ) -> Self {
+        OtpParams {
+            kind,
+            secret,
+            algorithm,
+            digits,
+            period,
+            counter,
+            issuer: String::new(),
+            account_name: String::new(),
+        }
+    }
+
+    pub fn with_label(mut self, issuer: String, account_name: String) -> Self {
+        self.issuer = issuer;
+        self.account_name = account_name;
+        self
+    }
+
+    pub fn get_kind(&self) -> OtpKindEnum {
+        self.kind.clone()
+    }
+
+    pub fn get_secret(&self) -> Vec<u8> {
+        self.secret.clone()
+    }
+
+    pub fn get_algorithm(&self) -> OtpAlgorithmEnum {
+        self.algorithm.clone()
+    }
+
+    pub fn get_digits(&self) -> u32 {
+        self.digits
+    }
+
+    pub fn get_period(&self) -> u64 {
+        self.period
+    }
+
+    pub fn get_counter(&self) -> u64 {
+        self.counter
+    }
+
+    pub fn get_issuer(&self) -> String {
+        self.issuer.clone()
+    }
+
+    pub fn get_account_name(&self) -> String {
+        self.account_name.clone()
+    }
+
+    pub fn is_counter_based(&self) -> bool {
+        self.kind == OtpKindEnum::Hotp
+    }
+
+    pub fn increment_counter(&mut self) {
+        self.counter += 1;
+    }
+
+    pub fn set_counter(&mut self, counter: u64) {
+        self.counter = counter;
+    }
+}
+
+impl OtpAlgorithmEnum {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            OtpAlgorithmEnum::Sha1 => "SHA1",
+            OtpAlgorithmEnum::Sha256 => "SHA256",
+            OtpAlgorithmEnum::Sha512 => "SHA512",
+        }
+    }
+}
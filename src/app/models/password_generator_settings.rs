@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum PasswordGeneratorModeEnum {
+    #[default]
+    Characters,
+    Passphrase,
+}
+
+/// The options of the password generator, remembered per vault.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct PasswordGeneratorSettings {
+    pub length: usize,
+    pub use_lowercase: bool,
+    pub use_uppercase: bool,
+    pub use_digits: bool,
+    pub use_symbols: bool,
+    pub exclude_ambiguous: bool,
+    pub min_lowercase: usize,
+    pub min_uppercase: usize,
+    pub min_digits: usize,
+    pub min_symbols: usize,
+    // Text the generated password starts with, counted in its length.
+    pub prefix: String,
+    #[serde(default)]
+    pub mode: PasswordGeneratorModeEnum,
+    #[serde(default = "default_word_count")]
+    pub word_count: usize,
+    #[serde(default = "default_separator")]
+    pub separator: String,
+    #[serde(default)]
+    pub capitalize: bool,
+    // Appends a random digit to one of the words.
+    #[serde(default)]
+    pub insert_digit: bool,
+    // A custom wordlist file. The embedded EFF wordlist is used when empty.
+    #[serde(default)]
+    pub wordlist_path: String,
+}
+
+fn default_word_count() -> usize {
+    6
+}
+
+fn default_separator() -> String {
+    "-".to_string()
+}
+
+impl PasswordGeneratorModeEnum {
+    pub fn next(&self) -> Self {
+        match self {
+            PasswordGeneratorModeEnum::Characters => PasswordGeneratorModeEnum::Passphrase,
+            PasswordGeneratorModeEnum::Passphrase => PasswordGeneratorModeEnum::Characters,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            PasswordGeneratorModeEnum::Characters => "characters",
+            PasswordGeneratorModeEnum::Passphrase => "passphrase",
+        }
+    }
+}
+
+impl Default for PasswordGeneratorSettings {
+    fn default() -> Self {
+        PasswordGeneratorSettings {
+            length: 20,
+            use_lowercase: true,
+            use_uppercase: true,
+            use_digits: true,
+            use_symbols: true,
+            exclude_ambiguous: false,
+            min_lowercase: 1,
+            min_uppercase: 1,
+            min_digits: 1,
+            min_symbols: 1,
+            prefix: String::new(),
+            mode: PasswordGeneratorModeEnum::Characters,
+            word_count: default_word_count(),
+            separator: default_separator(),
+            capitalize: false,
+            insert_digit: false,
+            wordlist_path: String::new(),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Credentials due within this many days are shown as due soon.
+pub const ROTATION_WARNING_DAYS: i64 = 14;
+
+/// When the password of a credential should be changed.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum RotationPolicyEnum {
+    /// Rotate the given number of days after the password was last changed.
+    EveryDays(u32),
+    /// The password expires on a fixed date.
+    ExpiresOn(NaiveDate),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum RotationStatusEnum {
+    Ok,
+    DueSoon,
+    Overdue,
+}
+
+impl RotationPolicyEnum {
+    /// Parses "90d", "90" or a "YYYY-MM-DD" date. An empty input means no policy.
+    pub fn parse(input: &str) -> Result<Option<Self>, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+
+        let days = input.strip_suffix(['d', 'D']).unwrap_or(input);
+        if let Ok(days) = days.trim().parse::<u32>() {
+            if days == 0 {
+                return Err("The rotation interval must be at least one day".to_string());
+            }
+            return Ok(Some(RotationPolicyEnum::EveryDays(days)));
+        }
+
+        NaiveDate::parse_from_str(input, "%Y-%m-%d")
+            .map(|date| Some(RotationPolicyEnum::ExpiresOn(date)))
+            .map_err(|_| {
+                format!(
+                    "Invalid rotation \"{}\", expected a number of days such as 90d or a date such as 2025-12-31",
+                    input
+                )
+            })
+    }
+
+    pub fn to_input_string(&self) -> String {
+        match self {
+            RotationPolicyEnum::EveryDays(days) => format!("{}d", days),
+            RotationPolicyEnum::ExpiresOn(date) => date.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    /// Returns when the password is due. An interval cannot be applied when it is not
+    /// known when the password was last changed.
+    pub fn get_due_date(
+        &self,
+        password_modified_at: Option<DateTime<Utc>>,
+    ) -> Option<DateTime<Utc>> {
+        match self {
+            RotationPolicyEnum::EveryDays(days) => {
+                password_modified_at.map(|changed| changed + Duration::days(*days as i64))
+            }
+            RotationPolicyEnum::ExpiresOn(date) => {
+                Some(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+            }
+        }
+    }
+}
+
+impl RotationStatusEnum {
+    pub fn is_overdue(&self) -> bool {
+        *self == RotationStatusEnum::Overdue
+    }
+
+    pub fn is_due_soon(&self) -> bool {
+        *self == RotationStatusEnum::DueSoon
+    }
+
+    pub fn from_due_date(due_date: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Self {
+        match due_date {
+            Some(due_date) if due_date <= now => RotationStatusEnum::Overdue,
+            Some(due_date) if due_date <= now + Duration::days(ROTATION_WARNING_DAYS) => {
+                RotationStatusEnum::DueSoon
+            }
+            _ => RotationStatusEnum::Ok,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(RotationPolicyEnum::parse("  ").unwrap(), None);
+        assert_eq!(
+            RotationPolicyEnum::parse("90d").unwrap(),
+            Some(RotationPolicyEnum::EveryDays(90))
+        );
+        assert_eq!(
+            RotationPolicyEnum::parse("30").unwrap(),
+            Some(RotationPolicyEnum::EveryDays(30))
+        );
+        assert_eq!(
+            RotationPolicyEnum::parse("2025-12-31").unwrap(),
+            Some(RotationPolicyEnum::ExpiresOn(
+                NaiveDate::from_ymd_opt(2025, 12, 31).unwrap()
+            ))
+        );
+        assert!(RotationPolicyEnum::parse("0d").is_err());
+        assert!(RotationPolicyEnum::parse("soon").is_err());
+    }
+
+    #[test]
+    fn test_status() {
+        let now = Utc::now();
+        let policy = RotationPolicyEnum::EveryDays(90);
+
+        let due_date = policy.get_due_date(Some(now - Duration::days(100)));
+        assert_eq!(
+            RotationStatusEnum::from_due_date(due_date, now),
+            RotationStatusEnum::Overdue
+        );
+
+        let due_date = policy.get_due_date(Some(now - Duration::days(80)));
+        assert_eq!(
+            RotationStatusEnum::from_due_date(due_date, now),
+            RotationStatusEnum::DueSoon
+        );
+
+        let due_date = policy.get_due_date(Some(now));
+        assert_eq!(
+            RotationStatusEnum::from_due_date(due_date, now),
+            RotationStatusEnum::Ok
+        );
+        assert_eq!(policy.get_due_date(None), None);
+    }
+}
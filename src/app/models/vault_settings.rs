@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::password_generator_settings::PasswordGeneratorSettings;
+
+/// Preferences stored inside the encrypted credentials, so they follow the vault.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct VaultSettings {
+    // Trashed credentials older than this are purged when the vault is opened. 0 keeps them.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    // Rotation interval in days for credentials with a given tag and no policy of their own.
+    #[serde(default)]
+    pub rotation_defaults: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub password_generator: PasswordGeneratorSettings,
+    // The health report flags passwords unchanged for longer than this. 0 disables the check.
+    #[serde(default = "default_password_max_age_days")]
+    pub password_max_age_days: u32,
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_password_max_age_days() -> u32 {
+    365
+}
+
+impl VaultSettings {
+    /// Formats the rotation defaults as "tag=days" pairs, the way they are edited.
+    pub fn format_rotation_defaults(&self) -> String {
+        self.rotation_defaults
+            .iter()
+            .map(|(tag, days)| format!("{}={}", tag, days))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// Parses comma-separated "tag=days" pairs. Tags are case-insensitive.
+    pub fn parse_rotation_defaults(input: &str) -> Result<BTreeMap<String, u32>, String> {
+        let mut rotation_defaults = BTreeMap::new();
+        for pair in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (tag, days) = pair
+                .split_once('=')
+                .ok_or(format!("Expected tag=days, got \"{}\"", pair))?;
+            let tag = tag.trim().to_lowercase();
+            let days = days.trim().trim_end_matches(['d', 'D']);
+            match days.parse::<u32>() {
+                Ok(days) if !tag.is_empty() && days > 0 => {
+                    rotation_defaults.insert(tag, days);
+                }
+                _ => return Err(format!("Expected tag=days, got \"{}\"", pair)),
+            }
+        }
+        Ok(rotation_defaults)
+    }
+}
+
+impl Default for VaultSettings {
+    fn default() -> Self {
+        VaultSettings {
+            trash_retention_days: default_trash_retention_days(),
+            rotation_defaults: BTreeMap::new(),
+            password_generator: PasswordGeneratorSettings::default(),
+            password_max_age_days: default_password_max_age_days(),
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use crate::app::models::otp_params::{
+    OtpAlgorithmEnum, OtpKindEnum, OtpParams, DEFAULT_OTP_DIGITS, DEFAULT_TOTP_PERIOD,
+    STEAM_GUARD_DIGITS,
+};
+
+use data_encoding::{Encoding, Specification, BASE32_NOPAD};
+use hmac::{Hmac, Mac};
+use percent_encoding::percent_decode_str;
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::error::Error;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+const STEAM_ALPHABET: &[u8] = b"23456789BCDFGHJKMNPQRTVWXY";
+
+#[derive(Debug)]
+pub enum OtpError {
+    InvalidSecret,
+    InvalidUri(String),
+}
+
+impl fmt::Display for OtpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OtpError::InvalidSecret => write!(f, "The OTP secret is not valid base32"),
+            OtpError::InvalidUri(reason) => write!(f, "Invalid otpauth URI: {}", reason),
+        }
+    }
+}
+
+impl Error for OtpError {}
+
+/// Parses a bare base32 TOTP secret, an `otpauth://` URI or a `steam://` secret.
+pub fn parse_otp(input: &str) -> Result<OtpParams, OtpError> {
+    let input = input.trim();
+
+    if input.starts_with("otpauth://") {
+        parse_otpauth_uri(input)
+    } else if let Some(secret) = input.strip_prefix("steam://") {
+        Ok(OtpParams::new(
+            OtpKindEnum::Steam,
+            decode_base32(secret)?,
+            OtpAlgorithmEnum::Sha1,
+            STEAM_GUARD_DIGITS,
+            DEFAULT_TOTP_PERIOD,
+            0,
+        ))
+    } else {
+        Ok(OtpParams::new(
+            OtpKindEnum::Totp,
+            decode_base32(input)?,
+            OtpAlgorithmEnum::Sha1,
+            DEFAULT_OTP_DIGITS,
+            DEFAULT_TOTP_PERIOD,
+            0,
+        ))
+    }
+}
+
+/// Decodes a base32 secret, ignoring case, spaces, dashes and padding.
+pub fn decode_base32(secret: &str) -> Result<Vec<u8>, OtpError> {
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-' && *c != '=')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    if normalized.is_empty() {
+        return Err(OtpError::InvalidSecret);
+    }
+
+    lenient_base32()
+        .decode(normalized.as_bytes())
+        .map_err(|_| OtpError::InvalidSecret)
+}
+
+// Some issuers hand out secrets whose last symbol has non-zero padding bits.
+fn lenient_base32() -> Encoding {
+    let mut spec = Specification::new();
+    spec.symbols.push_str("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567");
+    spec.check_trailing_bits = false;
+    spec.encoding().unwrap()
+}
+
+fn parse_otpauth_uri(uri: &str) -> Result<OtpParams, OtpError> {
+    let url = Url::parse(uri).map_err(|e| OtpError::InvalidUri(e.to_string()))?;
+
+    let label = percent_decode_str(url.path().trim_start_matches('/'))
+        .decode_utf8_lossy()
+        .to_string();
+    let (mut issuer, account_name) = match label.split_once(':') {
+        Some((issuer, account_name)) => (issuer.trim().to_string(), account_name.trim()),
+        None => (String::new(), label.trim()),
+    };
+
+    let mut secret = None;
+    let mut algorithm = OtpAlgorithmEnum::Sha1;
+    let mut digits = None;
+    let mut period = DEFAULT_TOTP_PERIOD;
+    let mut counter = None;
+    let mut is_steam = false;
+
+    for (key, value) in url.query_pairs() {
+        match key.to_ascii_lowercase().as_str() {
+            "secret" => secret = Some(decode_base32(&value)?),
+            "issuer" => issuer = value.trim().to_string(),
+            "algorithm" => {
+                algorithm = match value.to_ascii_uppercase().as_str() {
+                    "SHA1" => OtpAlgorithmEnum::Sha1,
+                    "SHA256" => OtpAlgorithmEnum::Sha256,
+                    "SHA512" => OtpAlgorithmEnum::Sha512,
+                    other => {
+                        return Err(OtpError::InvalidUri(format!(
+                            "unsupported algorithm {}",
+                            other
+                        )))
+                    }
+                }
+            }
+            "digits" => {
+                digits = Some(u32::try_from(parse_number(&key, &value)?).map_err(|_| {
+                    OtpError::InvalidUri(format!("unsupported number of digits {}", value))
+                })?)
+            }
+            "period" => period = parse_number(&key, &value)?,
+            "counter" => counter = Some(parse_number(&key, &value)?),
+            "encoder" => is_steam = value.eq_ignore_ascii_case("steam"),
+            _ => {}
+        }
+    }
+
+    let kind = match url.host_str().unwrap_or("").to_ascii_lowercase().as_str() {
+        "totp" if is_steam => OtpKindEnum::Steam,
+        "totp" => OtpKindEnum::Totp,
+        "hotp" => OtpKindEnum::Hotp,
+        "steam" => OtpKindEnum::Steam,
+        other => {
+            return Err(OtpError::InvalidUri(format!(
+                "unsupported OTP type '{}'",
+                other
+            )))
+        }
+    };
+
+    let secret = secret.ok_or(OtpError::InvalidUri("missing secret".to_string()))?;
+
+    let digits = match kind {
+        OtpKindEnum::Steam => STEAM_GUARD_DIGITS,
+        _ => digits.unwrap_or(DEFAULT_OTP_DIGITS),
+    };
+    if kind != OtpKindEnum::Steam && !(6..=10).contains(&digits) {
+        return Err(OtpError::InvalidUri(format!(
+            "unsupported number of digits {}",
+            digits
+        )));
+    }
+
+    if period == 0 {
+        return Err(OtpError::InvalidUri("period must be positive".to_string()));
+    }
+
+    let counter = match kind {
+        OtpKindEnum::Hotp => {
+            counter.ok_or(OtpError::InvalidUri("HOTP requires a counter".to_string()))?
+        }
+        _ => 0,
+    };
+
+    Ok(
+        OtpParams::new(kind, secret, algorithm, digits, period, counter)
+            .with_label(issuer, account_name.to_string()),
+    )
+}
+
+fn parse_number(key: &str, value: &str) -> Result<u64, OtpError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| OtpError::InvalidUri(format!("invalid {} '{}'", key, value)))
+}
+
+/// Formats the parameters as an `otpauth://` URI that `parse_otp` reads back unchanged.
+pub fn to_uri(params: &OtpParams) -> String {
+    let kind = match params.get_kind() {
+        OtpKindEnum::Totp | OtpKindEnum::Steam => "totp",
+        OtpKindEnum::Hotp => "hotp",
+    };
+
+    let issuer = params.get_issuer();
+    let label = if issuer.is_empty() {
+        params.get_account_name()
+    } else {
+        format!("{}:{}", issuer, params.get_account_name())
+    };
+
+    let mut url = Url::parse(&format!("otpauth://{}/", kind)).unwrap();
+    url.set_path(&label);
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("secret", &BASE32_NOPAD.encode(&params.get_secret()));
+        if !issuer.is_empty() {
+            query.append_pair("issuer", &issuer);
+        }
+        query.append_pair("algorithm", params.get_algorithm().get_name());
+        query.append_pair("digits", &params.get_digits().to_string());
+        match params.get_kind() {
+            OtpKindEnum::Totp => {
+                query.append_pair("period", &params.get_period().to_string());
+            }
+            OtpKindEnum::Steam => {
+                query.append_pair("period", &params.get_period().to_string());
+                query.append_pair("encoder", "steam");
+            }
+            OtpKindEnum::Hotp => {
+                query.append_pair("counter", &params.get_counter().to_string());
+            }
+        }
+    }
+
+    url.to_string()
+}
+
+pub fn get_unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Generates the code for `unix_time`, or for the stored counter in the case of HOTP.
+pub fn generate_code(params: &OtpParams, unix_time: u64) -> String {
+    let counter = match params.get_kind() {
+        OtpKindEnum::Hotp => params.get_counter(),
+        OtpKindEnum::Totp | OtpKindEnum::Steam => unix_time / params.get_period(),
+    };
+
+    let hash = hmac_digest(
+        &params.get_algorithm(),
+        &params.get_secret(),
+        &counter.to_be_bytes(),
+    );
+
+    // Dynamic truncation as described in RFC 4226 section 5.3.
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let mut binary = u32::from_be_bytes(hash[offset..offset + 4].try_into().unwrap()) & 0x7fff_ffff;
+
+    match params.get_kind() {
+        OtpKindEnum::Steam => (0..params.get_digits())
+            .map(|_| {
+                let c = STEAM_ALPHABET[(binary as usize) % STEAM_ALPHABET.len()] as char;
+                binary /= STEAM_ALPHABET.len() as u32;
+                c
+            })
+            .collect(),
+        OtpKindEnum::Totp | OtpKindEnum::Hotp => format!(
+            "{:0width$}",
+            binary as u64 % 10u64.pow(params.get_digits()),
+            width = params.get_digits() as usize
+        ),
+    }
+}
+
+/// Returns the number of seconds the current time based code stays valid.
+pub fn get_seconds_remaining(params: &OtpParams, unix_time: u64) -> u64 {
+    params.get_period() - unix_time % params.get_period()
+}
+
+fn hmac_digest(algorithm: &OtpAlgorithmEnum, key: &[u8], message: &[u8]) -> Vec<u8> {
+    // HMAC accepts keys of any length, so `new_from_slice` cannot fail.
+    match algorithm {
+        OtpAlgorithmEnum::Sha1 => {
+            let mut mac = Hmac::<Sha1>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        OtpAlgorithmEnum::Sha256 => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        OtpAlgorithmEnum::Sha512 => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(key).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rfc_params(algorithm: OtpAlgorithmEnum, secret: &[u8]) -> OtpParams {
+        OtpParams::new(OtpKindEnum::Totp, secret.to_vec(), algorithm, 8, 30, 0)
+    }
+
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        let sha1 = rfc_params(OtpAlgorithmEnum::Sha1, b"12345678901234567890");
+        let sha256 = rfc_params(
+            OtpAlgorithmEnum::Sha256,
+            b"12345678901234567890123456789012",
+        );
+        let sha512 = rfc_params(
+            OtpAlgorithmEnum::Sha512,
+            b"1234567890123456789012345678901234567890123456789012345678901234",
+        );
+
+        let vectors = [
+            (59, "94287082", "46119246", "90693936"),
+            (1111111109, "07081804", "68084774", "25091201"),
+            (1234567890, "89005924", "91819424", "93441116"),
+            (2000000000, "69279037", "90698825", "38618901"),
+            (20000000000, "65353130", "77737706", "47863826"),
+        ];
+
+        for (time, expected_sha1, expected_sha256, expected_sha512) in vectors {
+            assert_eq!(generate_code(&sha1, time), expected_sha1);
+            assert_eq!(generate_code(&sha256, time), expected_sha256);
+            assert_eq!(generate_code(&sha512, time), expected_sha512);
+        }
+    }
+
+    #[test]
+    fn test_hotp_rfc4226_vectors() {
+        let expected = [
+            "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583",
+            "399871", "520489",
+        ];
+
+        let mut params = OtpParams::new(
+            OtpKindEnum::Hotp,
+            b"12345678901234567890".to_vec(),
+            OtpAlgorithmEnum::Sha1,
+            6,
+            DEFAULT_TOTP_PERIOD,
+            0,
+        );
+        for code in expected {
+            assert_eq!(generate_code(&params, 0), code);
+            params.increment_counter();
+        }
+    }
+
+    #[test]
+    fn test_steam_code_uses_steam_alphabet() {
+        let params = parse_otp("steam://JBSWY3DPEHPK3PXP").unwrap();
+        let code = generate_code(&params, 1234567890);
+
+        assert_eq!(code.len(), 5);
+        assert!(code.bytes().all(|c| STEAM_ALPHABET.contains(&c)));
+    }
+
+    #[test]
+    fn test_parse_base32_secret() {
+        let params = parse_otp("jbsw y3dp ehpk 3pxp").unwrap();
+
+        assert_eq!(params.get_kind(), OtpKindEnum::Totp);
+        assert_eq!(params.get_secret(), b"Hello!\xde\xad\xbe\xef".to_vec());
+        assert_eq!(params.get_digits(), 6);
+        assert_eq!(params.get_period(), 30);
+        assert!(parse_otp("not base32!").is_err());
+    }
+
+    #[test]
+    fn test_parse_otpauth_uri() {
+        let params = parse_otp(
+            "otpauth://totp/ACME%20Co:john@example.com?secret=HXDMVJECJJWSRB3HWIZR4IFUGFTMXBOZ&issuer=ACME%20Co&algorithm=SHA256&digits=8&period=60",
+        )
+        .unwrap();
+
+        assert_eq!(params.get_kind(), OtpKindEnum::Totp);
+        assert_eq!(params.get_issuer(), "ACME Co");
+        assert_eq!(params.get_account_name(), "john@example.com");
+        assert_eq!(params.get_algorithm(), OtpAlgorithmEnum::Sha256);
+        assert_eq!(params.get_digits(), 8);
+        assert_eq!(params.get_period(), 60);
+
+        let hotp = parse_otp("otpauth://hotp/alice?secret=JBSWY3DPEHPK3PXP&counter=7").unwrap();
+        assert_eq!(hotp.get_kind(), OtpKindEnum::Hotp);
+        assert_eq!(hotp.get_counter(), 7);
+
+        assert!(parse_otp("otpauth://hotp/alice?secret=JBSWY3DPEHPK3PXP").is_err());
+        assert!(parse_otp("otpauth://totp/alice?digits=6").is_err());
+        assert!(
+            parse_otp("otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&digits=4294967302").is_err()
+        );
+    }
+
+    #[test]
+    fn test_uri_roundtrip() {
+        let inputs = [
+            "otpauth://totp/ACME%20Co:john@example.com?secret=HXDMVJECJJWSRB3HWIZR4IFUGFTMXBOZ&issuer=ACME%20Co",
+            "otpauth://hotp/alice?secret=JBSWY3DPEHPK3PXP&counter=7&algorithm=SHA512",
+            "otpauth://totp/Steam:gaben?secret=JBSWY3DPEHPK3PXP&issuer=Steam&encoder=steam",
+        ];
+
+        for input in inputs {
+            let params = parse_otp(input).unwrap();
+            assert_eq!(parse_otp(&to_uri(&params)).unwrap(), params);
+        }
+    }
+}
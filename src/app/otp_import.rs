@@ -0,0 +1,396 @@
+use crate::app::models::{
+    credentials::Credentials,
+    otp_params::{
+        OtpAlgorithmEnum, OtpKindEnum, OtpParams, DEFAULT_OTP_DIGITS, DEFAULT_TOTP_PERIOD,
+    },
+};
+use crate::app::otp;
+
+use data_encoding::BASE64_NOPAD;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use url::Url;
+
+const MIGRATION_SCHEME: &str = "otpauth-migration://";
+
+#[derive(Debug)]
+pub struct OtpImportError(String);
+
+impl fmt::Display for OtpImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Import failed: {}", self.0)
+    }
+}
+
+impl Error for OtpImportError {}
+
+/// An account found in the import source, along with the credential it would be attached to.
+pub struct OtpImportItem {
+    pub otp: OtpParams,
+    pub website: String,
+    pub email: String,
+    pub is_existing: bool, // whether the credential exists, or is created by the import.
+    pub accepted: bool,
+}
+
+/// Builds the preview of the imported accounts. Each account goes to its matching credential,
+/// or to a new one named after its issuer and account name, with a numbered placeholder when
+/// both are empty. A credential holds a single one-time password, so accounts that go to the
+/// same credential as an earlier one are skipped. Returns the items and the number skipped.
+pub fn get_import_items(
+    credentials: &Credentials,
+    accounts: Vec<OtpParams>,
+) -> (Vec<OtpImportItem>, usize) {
+    let mut items: Vec<OtpImportItem> = Vec::new();
+    let mut skipped = 0;
+    let mut unnamed = 0;
+
+    for otp_params in accounts {
+        let (website, email, is_existing) = match find_matching_credential(credentials, &otp_params)
+        {
+            Some((website, email)) => (website, email, true),
+            None => {
+                let issuer = otp_params.get_issuer();
+                let account_name = otp_params.get_account_name();
+                let website = match (issuer.is_empty(), account_name.is_empty()) {
+                    (false, _) => issuer,
+                    (true, false) => account_name.clone(),
+                    (true, true) => {
+                        unnamed += 1;
+                        format!("Unnamed one-time password {}", unnamed)
+                    }
+                };
+                (website, account_name, false)
+            }
+        };
+
+        if items
+            .iter()
+            .any(|item| item.website == website && item.email == email)
+        {
+            skipped += 1;
+            continue;
+        }
+        items.push(OtpImportItem {
+            otp: otp_params,
+            website,
+            email,
+            is_existing,
+            accepted: false,
+        });
+    }
+
+    (items, skipped)
+}
+
+/// Reads the accounts from a pasted `otpauth-migration://` or `otpauth://` URI, or from a
+/// text file containing one URI per line.
+pub fn read_import_source(source: &str) -> Result<Vec<OtpParams>, Box<dyn Error>> {
+    let source = source.trim();
+
+    if source.starts_with(MIGRATION_SCHEME) || source.starts_with("otpauth://") {
+        return parse_uri_list(source);
+    }
+
+    let contents = fs::read_to_string(source)
+        .map_err(|e| OtpImportError(format!("cannot read {}: {}", source, e)))?;
+    parse_uri_list(&contents)
+}
+
+/// Parses every non-empty line that is not a `#` comment.
+pub fn parse_uri_list(text: &str) -> Result<Vec<OtpParams>, Box<dyn Error>> {
+    let mut accounts = Vec::new();
+
+    for line in text.lines().map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with(MIGRATION_SCHEME) {
+            accounts.extend(decode_migration_uri(line)?);
+        } else if line.starts_with("otpauth://") {
+            accounts.push(otp::parse_otp(line)?);
+        } else {
+            return Err(OtpImportError(format!("unrecognized line '{}'", line)).into());
+        }
+    }
+
+    if accounts.is_empty() {
+        return Err(OtpImportError("no accounts found".to_string()).into());
+    }
+
+    Ok(accounts)
+}
+
+/// Decodes a Google Authenticator export, which is a base64-encoded `MigrationPayload`
+/// protobuf message in the `data` query parameter.
+pub fn decode_migration_uri(uri: &str) -> Result<Vec<OtpParams>, OtpImportError> {
+    let url = Url::parse(uri).map_err(|e| OtpImportError(e.to_string()))?;
+
+    let data = url
+        .query_pairs()
+        .find(|(key, _)| key == "data")
+        .map(|(_, value)| value.to_string())
+        .ok_or(OtpImportError("missing data parameter".to_string()))?;
+
+    // An unescaped '+' is decoded as a space by the query parser.
+    let data: String = data
+        .chars()
+        .filter(|c| *c != '=' && *c != '\n')
+        .map(|c| match c {
+            ' ' | '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    let payload = BASE64_NOPAD
+        .decode(data.as_bytes())
+        .map_err(|_| OtpImportError("data is not valid base64".to_string()))?;
+
+    let mut accounts = Vec::new();
+    let mut reader = ProtobufReader::new(&payload);
+    while let Some((field, value)) = reader.read_field()? {
+        // Field 1 is `repeated OtpParameters otp_parameters`; the batch fields are ignored.
+        if let (1, WireValue::Bytes(message)) = (field, value) {
+            accounts.push(decode_otp_parameters(message)?);
+        }
+    }
+
+    Ok(accounts)
+}
+
+fn decode_otp_parameters(message: &[u8]) -> Result<OtpParams, OtpImportError> {
+    let mut secret = Vec::new();
+    let mut name = String::new();
+    let mut issuer = String::new();
+    let mut algorithm = OtpAlgorithmEnum::Sha1;
+    let mut digits = DEFAULT_OTP_DIGITS;
+    let mut kind = OtpKindEnum::Totp;
+    let mut counter = 0;
+
+    let mut reader = ProtobufReader::new(message);
+    while let Some((field, value)) = reader.read_field()? {
+        match (field, value) {
+            (1, WireValue::Bytes(bytes)) => secret = bytes.to_vec(),
+            (2, WireValue::Bytes(bytes)) => name = String::from_utf8_lossy(bytes).to_string(),
+            (3, WireValue::Bytes(bytes)) => issuer = String::from_utf8_lossy(bytes).to_string(),
+            (4, WireValue::Varint(value)) => {
+                algorithm = match value {
+                    0 | 1 => OtpAlgorithmEnum::Sha1,
+                    2 => OtpAlgorithmEnum::Sha256,
+                    3 => OtpAlgorithmEnum::Sha512,
+                    _ => return Err(OtpImportError(format!("unsupported algorithm in {}", name))),
+                }
+            }
+            (5, WireValue::Varint(value)) => digits = if value == 2 { 8 } else { 6 },
+            (6, WireValue::Varint(value)) => {
+                kind = if value == 1 {
+                    OtpKindEnum::Hotp
+                } else {
+                    OtpKindEnum::Totp
+                }
+            }
+            (7, WireValue::Varint(value)) => counter = value,
+            _ => {}
+        }
+    }
+
+    if secret.is_empty() {
+        return Err(OtpImportError(format!("missing secret for {}", name)));
+    }
+
+    // Google Authenticator stores the label as "issuer:account".
+    let account_name = match name.split_once(':') {
+        Some((prefix, account_name)) if issuer.is_empty() || prefix == issuer => {
+            if issuer.is_empty() {
+                issuer = prefix.to_string();
+            }
+            account_name.trim().to_string()
+        }
+        _ => name.trim().to_string(),
+    };
+
+    Ok(OtpParams::new(
+        kind,
+        secret,
+        algorithm,
+        digits,
+        DEFAULT_TOTP_PERIOD,
+        counter,
+    )
+    .with_label(issuer, account_name))
+}
+
+/// Finds the credential an imported account belongs to: same website as the issuer, and the
+/// account name is either the email or the username.
+pub fn find_matching_credential(
+    credentials: &Credentials,
+    otp_params: &OtpParams,
+) -> Option<(String, String)> {
+    let issuer = otp_params.get_issuer().to_lowercase();
+    let account_name = otp_params.get_account_name();
+
+    credentials
+        .credentials
+        .iter()
+        .find(|c| {
+            let website = c.website.to_lowercase();
+            (website == issuer || (!issuer.is_empty() && website.contains(&issuer)))
+                && (c.email == account_name || c.username == account_name)
+        })
+        .map(|c| (c.website.clone(), c.email.clone()))
+}
+
+enum WireValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Reads just enough of the protobuf wire format to decode the migration payload.
+struct ProtobufReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ProtobufReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        ProtobufReader { data, position: 0 }
+    }
+
+    fn read_field(&mut self) -> Result<Option<(u64, WireValue<'a>)>, OtpImportError> {
+        if self.position >= self.data.len() {
+            return Ok(None);
+        }
+
+        let key = self.read_varint()?;
+        let value = match key & 0x07 {
+            0 => WireValue::Varint(self.read_varint()?),
+            1 => {
+                self.skip(8)?;
+                WireValue::Fixed
+            }
+            2 => {
+                let length = self.read_varint()? as usize;
+                WireValue::Bytes(self.skip(length)?)
+            }
+            5 => {
+                self.skip(4)?;
+                WireValue::Fixed
+            }
+            wire_type => {
+                return Err(OtpImportError(format!(
+                    "unsupported protobuf wire type {}",
+                    wire_type
+                )))
+            }
+        };
+
+        Ok(Some((key >> 3, value)))
+    }
+
+    fn read_varint(&mut self) -> Result<u64, OtpImportError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self
+                .data
+                .get(self.position)
+                .ok_or(OtpImportError("truncated payload".to_string()))?;
+            self.position += 1;
+
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(OtpImportError("malformed varint".to_string()))
+    }
+
+    fn skip(&mut self, length: usize) -> Result<&'a [u8], OtpImportError> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(OtpImportError("truncated payload".to_string()))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_migration_uri() {
+        let accounts = decode_migration_uri(
+            "otpauth-migration://offline?data=CjEKCkhlbGxvId6tvu8SGEV4YW1wbGU6YWxpY2VAZ29vZ2xlLmNvbRoHRXhhbXBsZTAC",
+        )
+        .unwrap();
+
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].get_kind(), OtpKindEnum::Totp);
+        assert_eq!(accounts[0].get_secret(), b"Hello!\xde\xad\xbe\xef".to_vec());
+        assert_eq!(accounts[0].get_issuer(), "Example");
+        assert_eq!(accounts[0].get_account_name(), "alice@google.com");
+        assert_eq!(accounts[0].get_digits(), 6);
+    }
+
+    #[test]
+    fn test_parse_uri_list() {
+        let text = "# exported accounts\n\
+            otpauth://totp/GitHub:octocat?secret=JBSWY3DPEHPK3PXP&issuer=GitHub\n\
+            \n\
+            otpauth-migration://offline?data=CjEKCkhlbGxvId6tvu8SGEV4YW1wbGU6YWxpY2VAZ29vZ2xlLmNvbRoHRXhhbXBsZTAC\n";
+
+        let accounts = parse_uri_list(text).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].get_issuer(), "GitHub");
+        assert_eq!(accounts[1].get_issuer(), "Example");
+
+        assert!(parse_uri_list("https://example.com").is_err());
+        assert!(parse_uri_list("# nothing here").is_err());
+    }
+
+    #[test]
+    fn test_get_import_items() {
+        let mut credentials = Credentials::new();
+        credentials.add_or_update_credential(crate::app::models::credentials::Credential::new(
+            Some("github.com".to_string()),
+            Some("octocat@example.com".to_string()),
+            Some("octocat".to_string()),
+            None,
+            None,
+        ));
+        let text = "otpauth://totp/GitHub:octocat?secret=JBSWY3DPEHPK3PXP&issuer=GitHub\n\
+            otpauth://totp/Example:alice?secret=JBSWY3DPEHPK3PXP&issuer=Example\n\
+            otpauth://totp/Example:alice?secret=KRSXG5CTMVRXEZLU&issuer=Example\n\
+            otpauth://totp/?secret=JBSWY3DPEHPK3PXP\n\
+            otpauth://totp/?secret=KRSXG5CTMVRXEZLU\n";
+
+        let (items, skipped) = get_import_items(&credentials, parse_uri_list(text).unwrap());
+        let targets: Vec<(&str, &str, bool)> = items
+            .iter()
+            .map(|item| (item.website.as_str(), item.email.as_str(), item.is_existing))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                ("github.com", "octocat@example.com", true),
+                ("Example", "alice", false),
+                ("Unnamed one-time password 1", "", false),
+                ("Unnamed one-time password 2", "", false),
+            ]
+        );
+        // The second Example:alice would overwrite the secret of the first.
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_truncated_payload_is_rejected() {
+        // The first message claims 49 bytes but only a few follow.
+        assert!(decode_migration_uri("otpauth-migration://offline?data=CjEKCkhlbGxv").is_err());
+    }
+}
@@ -0,0 +1,554 @@
+pub use crate::app::models::password_generator_settings::{
+    PasswordGeneratorModeEnum, PasswordGeneratorSettings,
+};
+
+use super::{password_rules::PasswordRules, wordlist};
+
+use rand::rngs::OsRng;
+use rand::Rng;
+use std::error::Error;
+use std::fmt;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+/// Characters that are easily confused with one another when read or typed.
+const AMBIGUOUS: &str = "0O1lI|`'\"";
+
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+pub const MAX_WORD_COUNT: usize = 20;
+
+#[derive(Debug)]
+pub enum PasswordGeneratorError {
+    NoCharacterClass,
+    TooShort(usize),
+    Wordlist(String),
+    Rules(String),
+}
+
+impl fmt::Display for PasswordGeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PasswordGeneratorError::NoCharacterClass => {
+                write!(f, "Select at least one character class")
+            }
+            PasswordGeneratorError::TooShort(required) => write!(
+                f,
+                "The length must be at least {} for the prefix and minimum counts",
+                required
+            ),
+            PasswordGeneratorError::Wordlist(reason) => {
+                write!(f, "Could not load the wordlist: {}", reason)
+            }
+            PasswordGeneratorError::Rules(reason) => {
+                write!(
+                    f,
+                    "The password does not follow the site's rules: {}",
+                    reason
+                )
+            }
+        }
+    }
+}
+
+impl Error for PasswordGeneratorError {}
+
+/// The options shown in the generator popup, in display order.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GeneratorOptionEnum {
+    Mode,
+    Length,
+    Lowercase,
+    Uppercase,
+    Digits,
+    Symbols,
+    ExcludeAmbiguous,
+    MinLowercase,
+    MinUppercase,
+    MinDigits,
+    MinSymbols,
+    Prefix,
+    WordCount,
+    Separator,
+    Capitalize,
+    InsertDigit,
+    Wordlist,
+}
+
+impl GeneratorOptionEnum {
+    const CHARACTER_OPTIONS: [GeneratorOptionEnum; 12] = [
+        GeneratorOptionEnum::Mode,
+        GeneratorOptionEnum::Length,
+        GeneratorOptionEnum::Lowercase,
+        GeneratorOptionEnum::Uppercase,
+        GeneratorOptionEnum::Digits,
+        GeneratorOptionEnum::Symbols,
+        GeneratorOptionEnum::ExcludeAmbiguous,
+        GeneratorOptionEnum::MinLowercase,
+        GeneratorOptionEnum::MinUppercase,
+        GeneratorOptionEnum::MinDigits,
+        GeneratorOptionEnum::MinSymbols,
+        GeneratorOptionEnum::Prefix,
+    ];
+
+    const PASSPHRASE_OPTIONS: [GeneratorOptionEnum; 6] = [
+        GeneratorOptionEnum::Mode,
+        GeneratorOptionEnum::WordCount,
+        GeneratorOptionEnum::Separator,
+        GeneratorOptionEnum::Capitalize,
+        GeneratorOptionEnum::InsertDigit,
+        GeneratorOptionEnum::Wordlist,
+    ];
+
+    /// Returns the options that apply to the mode, in display order.
+    pub fn get_options(mode: PasswordGeneratorModeEnum) -> &'static [GeneratorOptionEnum] {
+        match mode {
+            PasswordGeneratorModeEnum::Characters => &Self::CHARACTER_OPTIONS,
+            PasswordGeneratorModeEnum::Passphrase => &Self::PASSPHRASE_OPTIONS,
+        }
+    }
+
+    /// Text options are edited by typing instead of with the arrow keys.
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            GeneratorOptionEnum::Prefix
+                | GeneratorOptionEnum::Separator
+                | GeneratorOptionEnum::Wordlist
+        )
+    }
+
+    pub fn get_text_mut<'a>(
+        &self,
+        settings: &'a mut PasswordGeneratorSettings,
+    ) -> Option<&'a mut String> {
+        match self {
+            GeneratorOptionEnum::Prefix => Some(&mut settings.prefix),
+            GeneratorOptionEnum::Separator => Some(&mut settings.separator),
+            GeneratorOptionEnum::Wordlist => Some(&mut settings.wordlist_path),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            GeneratorOptionEnum::Mode => "Mode",
+            GeneratorOptionEnum::Length => "Length",
+            GeneratorOptionEnum::Lowercase => "Lowercase (a-z)",
+            GeneratorOptionEnum::Uppercase => "Uppercase (A-Z)",
+            GeneratorOptionEnum::Digits => "Digits (0-9)",
+            GeneratorOptionEnum::Symbols => "Symbols (!@#...)",
+            GeneratorOptionEnum::ExcludeAmbiguous => "Exclude ambiguous (0O1lI|)",
+            GeneratorOptionEnum::MinLowercase => "Minimum lowercase",
+            GeneratorOptionEnum::MinUppercase => "Minimum uppercase",
+            GeneratorOptionEnum::MinDigits => "Minimum digits",
+            GeneratorOptionEnum::MinSymbols => "Minimum symbols",
+            GeneratorOptionEnum::Prefix => "Prefix",
+            GeneratorOptionEnum::WordCount => "Words",
+            GeneratorOptionEnum::Separator => "Separator",
+            GeneratorOptionEnum::Capitalize => "Capitalize words",
+            GeneratorOptionEnum::InsertDigit => "Insert a digit",
+            GeneratorOptionEnum::Wordlist => "Wordlist file (empty for EFF)",
+        }
+    }
+
+    pub fn get_value(&self, settings: &PasswordGeneratorSettings) -> String {
+        let checkbox = |enabled: bool| if enabled { "[x]" } else { "[ ]" }.to_string();
+        match self {
+            GeneratorOptionEnum::Mode => settings.mode.get_name().to_string(),
+            GeneratorOptionEnum::Length => settings.length.to_string(),
+            GeneratorOptionEnum::Lowercase => checkbox(settings.use_lowercase),
+            GeneratorOptionEnum::Uppercase => checkbox(settings.use_uppercase),
+            GeneratorOptionEnum::Digits => checkbox(settings.use_digits),
+            GeneratorOptionEnum::Symbols => checkbox(settings.use_symbols),
+            GeneratorOptionEnum::ExcludeAmbiguous => checkbox(settings.exclude_ambiguous),
+            GeneratorOptionEnum::MinLowercase => settings.min_lowercase.to_string(),
+            GeneratorOptionEnum::MinUppercase => settings.min_uppercase.to_string(),
+            GeneratorOptionEnum::MinDigits => settings.min_digits.to_string(),
+            GeneratorOptionEnum::MinSymbols => settings.min_symbols.to_string(),
+            GeneratorOptionEnum::Prefix => settings.prefix.clone(),
+            GeneratorOptionEnum::WordCount => settings.word_count.to_string(),
+            GeneratorOptionEnum::Separator => format!("\"{}\"", settings.separator),
+            GeneratorOptionEnum::Capitalize => checkbox(settings.capitalize),
+            GeneratorOptionEnum::InsertDigit => checkbox(settings.insert_digit),
+            GeneratorOptionEnum::Wordlist => settings.wordlist_path.clone(),
+        }
+    }
+
+    /// Flips a checkbox option. Other options are left unchanged.
+    pub fn toggle(&self, settings: &mut PasswordGeneratorSettings) {
+        match self {
+            GeneratorOptionEnum::Mode => settings.mode = settings.mode.next(),
+            GeneratorOptionEnum::Lowercase => settings.use_lowercase = !settings.use_lowercase,
+            GeneratorOptionEnum::Uppercase => settings.use_uppercase = !settings.use_uppercase,
+            GeneratorOptionEnum::Digits => settings.use_digits = !settings.use_digits,
+            GeneratorOptionEnum::Symbols => settings.use_symbols = !settings.use_symbols,
+            GeneratorOptionEnum::ExcludeAmbiguous => {
+                settings.exclude_ambiguous = !settings.exclude_ambiguous
+            }
+            GeneratorOptionEnum::Capitalize => settings.capitalize = !settings.capitalize,
+            GeneratorOptionEnum::InsertDigit => settings.insert_digit = !settings.insert_digit,
+            _ => {}
+        }
+    }
+
+    /// Increases or decreases a numeric option. Checkboxes are toggled instead.
+    pub fn adjust(&self, settings: &mut PasswordGeneratorSettings, delta: i64) {
+        let adjust =
+            |value: usize| (value as i64 + delta).clamp(0, MAX_PASSWORD_LENGTH as i64) as usize;
+        match self {
+            GeneratorOptionEnum::Length => settings.length = adjust(settings.length).max(1),
+            GeneratorOptionEnum::MinLowercase => {
+                settings.min_lowercase = adjust(settings.min_lowercase)
+            }
+            GeneratorOptionEnum::MinUppercase => {
+                settings.min_uppercase = adjust(settings.min_uppercase)
+            }
+            GeneratorOptionEnum::MinDigits => settings.min_digits = adjust(settings.min_digits),
+            GeneratorOptionEnum::MinSymbols => settings.min_symbols = adjust(settings.min_symbols),
+            GeneratorOptionEnum::WordCount => {
+                settings.word_count =
+                    (settings.word_count as i64 + delta).clamp(1, MAX_WORD_COUNT as i64) as usize
+            }
+            _ if self.is_text() => {}
+            _ => self.toggle(settings),
+        }
+    }
+}
+
+/// Returns the enabled character classes with their minimum counts.
+fn get_character_classes(settings: &PasswordGeneratorSettings) -> Vec<(Vec<char>, usize)> {
+    let classes = [
+        (settings.use_lowercase, LOWERCASE, settings.min_lowercase),
+        (settings.use_uppercase, UPPERCASE, settings.min_uppercase),
+        (settings.use_digits, DIGITS, settings.min_digits),
+        (settings.use_symbols, SYMBOLS, settings.min_symbols),
+    ];
+
+    classes
+        .iter()
+        .filter(|(enabled, _, _)| *enabled)
+        .map(|(_, characters, minimum)| {
+            let characters = characters
+                .chars()
+                .filter(|c| !settings.exclude_ambiguous || !AMBIGUOUS.contains(*c))
+                .collect();
+            (characters, *minimum)
+        })
+        .collect()
+}
+
+/// Generates a password or a passphrase from the settings using the operating system's
+/// random generator. When the site has password rules, passwords are generated from them
+/// instead of the character settings, and passphrases are checked against them.
+pub fn generate_password(
+    settings: &PasswordGeneratorSettings,
+    rules: Option<&PasswordRules>,
+) -> Result<String, PasswordGeneratorError> {
+    match (settings.mode, rules) {
+        (PasswordGeneratorModeEnum::Characters, Some(rules)) => {
+            generate_with_rules(settings, rules)
+        }
+        (PasswordGeneratorModeEnum::Characters, None) => generate_characters(settings),
+        (PasswordGeneratorModeEnum::Passphrase, Some(rules)) => {
+            let passphrase = generate_passphrase(settings)?;
+            rules
+                .check(&passphrase)
+                .map_err(PasswordGeneratorError::Rules)?;
+            Ok(passphrase)
+        }
+        (PasswordGeneratorModeEnum::Passphrase, None) => generate_passphrase(settings),
+    }
+}
+
+/// Returns the length used for a password following the rules: the configured length
+/// brought within the rules' bounds, which never exceed `MAX_PASSWORD_LENGTH`.
+fn get_length_with_rules(settings: &PasswordGeneratorSettings, rules: &PasswordRules) -> usize {
+    let mut length = settings
+        .length
+        .max(rules.required.len())
+        .min(MAX_PASSWORD_LENGTH);
+    if let Some(max_length) = rules.max_length {
+        length = length.min(max_length);
+    }
+    if let Some(min_length) = rules.min_length {
+        length = length.max(min_length);
+    }
+    length
+}
+
+fn generate_with_rules(
+    settings: &PasswordGeneratorSettings,
+    rules: &PasswordRules,
+) -> Result<String, PasswordGeneratorError> {
+    if rules.required.iter().any(|required| required.is_empty()) {
+        return Err(PasswordGeneratorError::Rules(
+            "a required character set is empty".to_string(),
+        ));
+    }
+    let length = get_length_with_rules(settings, rules);
+    if length == 0 || length < rules.required.len() {
+        return Err(PasswordGeneratorError::Rules(format!(
+            "{} required character sets do not fit in {} characters",
+            rules.required.len(),
+            length
+        )));
+    }
+
+    let mut rng = OsRng;
+    // The max-consecutive rule is met by drawing again, which rarely takes more than a few tries.
+    for _ in 0..1000 {
+        let mut characters: Vec<char> = rules
+            .required
+            .iter()
+            .map(|required| required[rng.gen_range(0..required.len())])
+            .collect();
+        while characters.len() < length {
+            characters.push(rules.allowed[rng.gen_range(0..rules.allowed.len())]);
+        }
+        for i in (1..characters.len()).rev() {
+            let j = rng.gen_range(0..=i);
+            characters.swap(i, j);
+        }
+
+        let password: String = characters.into_iter().collect();
+        if rules.check(&password).is_ok() {
+            return Ok(password);
+        }
+    }
+
+    Err(PasswordGeneratorError::Rules(
+        "no password could be generated".to_string(),
+    ))
+}
+
+fn generate_characters(
+    settings: &PasswordGeneratorSettings,
+) -> Result<String, PasswordGeneratorError> {
+    let classes = get_character_classes(settings);
+    if classes.is_empty() {
+        return Err(PasswordGeneratorError::NoCharacterClass);
+    }
+
+    let prefix_length = settings.prefix.chars().count();
+    let required = prefix_length + classes.iter().map(|(_, minimum)| minimum).sum::<usize>();
+    if settings.length < required || settings.length <= prefix_length {
+        return Err(PasswordGeneratorError::TooShort(
+            required.max(prefix_length + 1),
+        ));
+    }
+
+    let mut rng = OsRng;
+    let pool: Vec<char> = classes
+        .iter()
+        .flat_map(|(characters, _)| characters.clone())
+        .collect();
+
+    let mut characters: Vec<char> = Vec::with_capacity(settings.length);
+    for (class_characters, minimum) in &classes {
+        for _ in 0..*minimum {
+            characters.push(class_characters[rng.gen_range(0..class_characters.len())]);
+        }
+    }
+    while characters.len() < settings.length - prefix_length {
+        characters.push(pool[rng.gen_range(0..pool.len())]);
+    }
+
+    // Shuffle so that the required characters are not always at the start.
+    for i in (1..characters.len()).rev() {
+        let j = rng.gen_range(0..=i);
+        characters.swap(i, j);
+    }
+
+    let mut password = settings.prefix.clone();
+    password.extend(characters);
+    Ok(password)
+}
+
+fn generate_passphrase(
+    settings: &PasswordGeneratorSettings,
+) -> Result<String, PasswordGeneratorError> {
+    let wordlist = wordlist::load_wordlist(&settings.wordlist_path)
+        .map_err(|err| PasswordGeneratorError::Wordlist(err.to_string()))?;
+
+    let mut rng = OsRng;
+    let mut words: Vec<String> = (0..settings.word_count.max(1))
+        .map(|_| {
+            let word = &wordlist[rng.gen_range(0..wordlist.len())];
+            if settings.capitalize {
+                capitalize(word)
+            } else {
+                word.clone()
+            }
+        })
+        .collect();
+
+    if settings.insert_digit {
+        let index = rng.gen_range(0..words.len());
+        words[index].push(char::from(b'0' + rng.gen_range(0..10u8)));
+    }
+
+    Ok(words.join(&settings.separator))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut characters = word.chars();
+    match characters.next() {
+        Some(first) => first.to_uppercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+/// Estimates the entropy in bits of a password generated with the settings. The prefix is
+/// considered known and the minimum counts are ignored, which slightly overestimates.
+pub fn get_entropy_bits(
+    settings: &PasswordGeneratorSettings,
+    rules: Option<&PasswordRules>,
+) -> f64 {
+    if settings.mode == PasswordGeneratorModeEnum::Passphrase {
+        return get_passphrase_entropy_bits(settings);
+    }
+    if let Some(rules) = rules {
+        let length = get_length_with_rules(settings, rules);
+        return length as f64 * (rules.allowed.len() as f64).log2();
+    }
+
+    let pool_size: usize = get_character_classes(settings)
+        .iter()
+        .map(|(characters, _)| characters.len())
+        .sum();
+    if pool_size == 0 {
+        return 0.0;
+    }
+
+    let random_length = settings
+        .length
+        .saturating_sub(settings.prefix.chars().count());
+    random_length as f64 * (pool_size as f64).log2()
+}
+
+/// The entropy of a passphrase comes from the words alone, plus the digit and its position
+/// when one is inserted. Capitalization and the separator are considered known.
+fn get_passphrase_entropy_bits(settings: &PasswordGeneratorSettings) -> f64 {
+    let wordlist_size = match wordlist::load_wordlist(&settings.wordlist_path) {
+        Ok(words) => words.len(),
+        Err(_) => return 0.0,
+    };
+
+    let word_count = settings.word_count.max(1);
+    let mut bits = word_count as f64 * (wordlist_size as f64).log2();
+    if settings.insert_digit {
+        bits += (10.0 * word_count as f64).log2();
+    }
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_password() {
+        let settings = PasswordGeneratorSettings {
+            length: 12,
+            use_symbols: false,
+            exclude_ambiguous: true,
+            min_digits: 4,
+            prefix: "ab".to_string(),
+            ..PasswordGeneratorSettings::default()
+        };
+
+        for _ in 0..20 {
+            let password = generate_password(&settings, None).unwrap();
+            assert_eq!(password.chars().count(), 12);
+            assert!(password.starts_with("ab"));
+            assert!(password[2..].chars().filter(|c| c.is_ascii_digit()).count() >= 4);
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+            assert!(!password.chars().any(|c| AMBIGUOUS.contains(c)));
+        }
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        let mut settings = PasswordGeneratorSettings {
+            use_lowercase: false,
+            use_uppercase: false,
+            use_digits: false,
+            use_symbols: false,
+            ..PasswordGeneratorSettings::default()
+        };
+        assert!(generate_password(&settings, None).is_err());
+
+        settings.use_digits = true;
+        settings.min_digits = 30;
+        assert!(generate_password(&settings, None).is_err());
+    }
+
+    #[test]
+    fn test_generate_passphrase() {
+        let settings = PasswordGeneratorSettings {
+            mode: PasswordGeneratorModeEnum::Passphrase,
+            word_count: 5,
+            separator: ".".to_string(),
+            capitalize: true,
+            insert_digit: true,
+            ..PasswordGeneratorSettings::default()
+        };
+
+        let passphrase = generate_password(&settings, None).unwrap();
+        let words: Vec<&str> = passphrase.split('.').collect();
+        assert_eq!(words.len(), 5);
+        assert!(words
+            .iter()
+            .all(|w| w.starts_with(|c: char| c.is_uppercase())));
+        assert_eq!(passphrase.chars().filter(|c| c.is_ascii_digit()).count(), 1);
+
+        // 7776 words give log2(7776) ~ 12.925 bits per word.
+        let expected = 5.0 * 7776f64.log2() + 50f64.log2();
+        assert!((get_entropy_bits(&settings, None) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_generate_with_rules() {
+        let rules = PasswordRules::parse(
+            "minlength: 8; maxlength: 12; required: upper; required: [#]; allowed: lower; max-consecutive: 1",
+        )
+        .unwrap();
+        let settings = PasswordGeneratorSettings {
+            length: 30,
+            ..PasswordGeneratorSettings::default()
+        };
+
+        for _ in 0..20 {
+            let password = generate_password(&settings, Some(&rules)).unwrap();
+            assert_eq!(password.chars().count(), 12);
+            assert!(rules.check(&password).is_ok());
+            assert!(!password.chars().any(|c| c.is_ascii_digit()));
+        }
+
+        // Rules built by hand may hold an empty set, which the parser rejects.
+        let mut rules = PasswordRules::parse("required: digit").unwrap();
+        rules.required.push(Vec::new());
+        assert!(generate_password(&settings, Some(&rules)).is_err());
+
+        let mut rules = PasswordRules::parse("required: upper; required: digit").unwrap();
+        rules.max_length = Some(1);
+        assert!(generate_password(&settings, Some(&rules)).is_err());
+        rules.required.clear();
+        rules.max_length = Some(0);
+        assert!(generate_password(&settings, Some(&rules)).is_err());
+    }
+
+    #[test]
+    fn test_entropy() {
+        let settings = PasswordGeneratorSettings {
+            length: 10,
+            use_lowercase: false,
+            use_uppercase: false,
+            use_symbols: false,
+            ..PasswordGeneratorSettings::default()
+        };
+        assert!((get_entropy_bits(&settings, None) - 10.0 * 10f64.log2()).abs() < 1e-9);
+    }
+}
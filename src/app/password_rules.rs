@@ -0,0 +1,387 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use url::Url;
+
+use super::password_generator::MAX_PASSWORD_LENGTH;
+
+/// Password rules of known sites, in the format of Apple's password-manager-resources quirks.
+const PASSWORD_RULES_DATABASE: &str = include_str!("quirks/password-rules.json");
+
+const UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+const DIGIT: &str = "0123456789";
+const SPECIAL: &str = " -~!@#$%^&*_+=`|(){}[:;\"'<>,.?]";
+
+#[derive(Debug)]
+pub struct PasswordRulesError(String);
+
+impl fmt::Display for PasswordRulesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid password rules: {}", self.0)
+    }
+}
+
+impl Error for PasswordRulesError {}
+
+/// Rules written in Apple's `passwordrules` syntax, for example
+/// `required: upper; allowed: ascii-printable; max-consecutive: 2; maxlength: 20`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PasswordRules {
+    // The password contains at least one character of each set.
+    pub required: Vec<Vec<char>>,
+    // Every character of the password is in this set, which includes the required sets.
+    pub allowed: Vec<char>,
+    pub max_consecutive: Option<usize>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct PasswordRulesQuirk {
+    #[serde(rename = "password-rules")]
+    password_rules: String,
+}
+
+impl PasswordRules {
+    /// Parses rules. Unknown properties are ignored, as browsers do.
+    pub fn parse(input: &str) -> Result<Self, PasswordRulesError> {
+        let chars: Vec<char> = input.chars().collect();
+        let mut i = 0;
+        let mut rules = PasswordRules {
+            required: Vec::new(),
+            allowed: Vec::new(),
+            max_consecutive: None,
+            min_length: None,
+            max_length: None,
+        };
+
+        loop {
+            while i < chars.len() && (chars[i].is_whitespace() || chars[i] == ';') {
+                i += 1;
+            }
+            if i >= chars.len() {
+                break;
+            }
+
+            let name_start = i;
+            while i < chars.len() && chars[i] != ':' && chars[i] != ';' {
+                i += 1;
+            }
+            if i >= chars.len() || chars[i] != ':' {
+                let name: String = chars[name_start..i].iter().collect();
+                return Err(PasswordRulesError(format!(
+                    "expected \"name: value\", got \"{}\"",
+                    name.trim()
+                )));
+            }
+            let name: String = chars[name_start..i].iter().collect();
+            i += 1;
+
+            match name.trim().to_lowercase().as_str() {
+                "required" => {
+                    let characters = parse_classes(&chars, &mut i)?;
+                    rules.allowed.extend(&characters);
+                    rules.required.push(characters);
+                }
+                "allowed" => {
+                    let characters = parse_classes(&chars, &mut i)?;
+                    rules.allowed.extend(characters);
+                }
+                "max-consecutive" => rules.max_consecutive = Some(parse_number(&chars, &mut i)?),
+                "minlength" => rules.min_length = Some(parse_number(&chars, &mut i)?),
+                "maxlength" => rules.max_length = Some(parse_number(&chars, &mut i)?),
+                _ => {
+                    while i < chars.len() && chars[i] != ';' {
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        if rules.allowed.is_empty() {
+            rules.allowed = get_ascii_printable();
+        }
+        rules.allowed.sort();
+        rules.allowed.dedup();
+
+        for (name, length) in [
+            ("minlength", rules.min_length),
+            ("maxlength", rules.max_length),
+        ] {
+            if let Some(length) = length.filter(|&length| length > MAX_PASSWORD_LENGTH) {
+                return Err(PasswordRulesError(format!(
+                    "{} {} is greater than {}",
+                    name, length, MAX_PASSWORD_LENGTH
+                )));
+            }
+        }
+        if let Some(max_length) = rules.max_length {
+            if max_length == 0 {
+                return Err(PasswordRulesError(
+                    "maxlength must be at least 1".to_string(),
+                ));
+            }
+            if max_length < rules.required.len() {
+                return Err(PasswordRulesError(format!(
+                    "maxlength {} is too short for {} required character sets",
+                    max_length,
+                    rules.required.len()
+                )));
+            }
+        }
+        if let (Some(min_length), Some(max_length)) = (rules.min_length, rules.max_length) {
+            if min_length > max_length {
+                return Err(PasswordRulesError(format!(
+                    "minlength {} is greater than maxlength {}",
+                    min_length, max_length
+                )));
+            }
+        }
+
+        Ok(rules)
+    }
+
+    /// Returns why the password does not follow the rules, if it does not.
+    pub fn check(&self, password: &str) -> Result<(), String> {
+        let length = password.chars().count();
+        if let Some(min_length) = self.min_length {
+            if length < min_length {
+                return Err(format!("shorter than {} characters", min_length));
+            }
+        }
+        if let Some(max_length) = self.max_length {
+            if length > max_length {
+                return Err(format!("longer than {} characters", max_length));
+            }
+        }
+        if let Some(c) = password.chars().find(|c| !self.allowed.contains(c)) {
+            return Err(format!("'{}' is not allowed", c));
+        }
+        for required in &self.required {
+            if !password.chars().any(|c| required.contains(&c)) {
+                return Err(format!(
+                    "one of \"{}\" is required",
+                    required.iter().collect::<String>()
+                ));
+            }
+        }
+        if let Some(max_consecutive) = self.max_consecutive {
+            if get_longest_run(password) > max_consecutive {
+                return Err(format!(
+                    "more than {} identical characters in a row",
+                    max_consecutive
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the length of the longest run of identical consecutive characters.
+pub fn get_longest_run(password: &str) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous = None;
+    for c in password.chars() {
+        run = if previous == Some(c) { run + 1 } else { 1 };
+        longest = longest.max(run);
+        previous = Some(c);
+    }
+    longest
+}
+
+fn get_ascii_printable() -> Vec<char> {
+    (' '..='~').collect()
+}
+
+/// Parses a comma-separated list of character classes up to the next ';'.
+fn parse_classes(chars: &[char], i: &mut usize) -> Result<Vec<char>, PasswordRulesError> {
+    let mut characters = Vec::new();
+
+    loop {
+        while *i < chars.len() && chars[*i].is_whitespace() {
+            *i += 1;
+        }
+        if *i >= chars.len() || chars[*i] == ';' {
+            break;
+        }
+
+        if chars[*i] == '[' {
+            characters.extend(parse_custom_class(chars, i)?);
+        } else {
+            let start = *i;
+            while *i < chars.len() && !matches!(chars[*i], ',' | ';') {
+                *i += 1;
+            }
+            let name: String = chars[start..*i].iter().collect();
+            match name.trim().to_lowercase().as_str() {
+                "upper" => characters.extend(UPPER.chars()),
+                "lower" => characters.extend(LOWER.chars()),
+                "digit" => characters.extend(DIGIT.chars()),
+                "special" => characters.extend(SPECIAL.chars()),
+                // Generated passwords stay within ASCII, so unicode allows the same characters.
+                "ascii-printable" | "unicode" => characters.extend(get_ascii_printable()),
+                other => {
+                    return Err(PasswordRulesError(format!(
+                        "unknown character class \"{}\"",
+                        other
+                    )))
+                }
+            }
+        }
+
+        while *i < chars.len() && chars[*i].is_whitespace() {
+            *i += 1;
+        }
+        if *i < chars.len() && chars[*i] == ',' {
+            *i += 1;
+        }
+    }
+
+    if characters.is_empty() {
+        return Err(PasswordRulesError(
+            "empty list of character classes".to_string(),
+        ));
+    }
+    Ok(characters)
+}
+
+/// Parses a custom class such as `[-().&@?'#,/"+]`. A ']' belongs to the class unless it is
+/// followed by the end of the list, which allows it as the last character as in `[-]]`.
+fn parse_custom_class(chars: &[char], i: &mut usize) -> Result<Vec<char>, PasswordRulesError> {
+    *i += 1;
+    let start = *i;
+
+    while *i < chars.len() {
+        if chars[*i] == ']' && *i > start {
+            let mut next = *i + 1;
+            while next < chars.len() && chars[next].is_whitespace() {
+                next += 1;
+            }
+            if next >= chars.len() || matches!(chars[next], ',' | ';') {
+                let characters = chars[start..*i].to_vec();
+                *i += 1;
+                return Ok(characters);
+            }
+        }
+        *i += 1;
+    }
+
+    Err(PasswordRulesError(
+        "unterminated custom character class".to_string(),
+    ))
+}
+
+fn parse_number(chars: &[char], i: &mut usize) -> Result<usize, PasswordRulesError> {
+    let start = *i;
+    while *i < chars.len() && chars[*i] != ';' {
+        *i += 1;
+    }
+    let value: String = chars[start..*i].iter().collect();
+    value
+        .trim()
+        .parse()
+        .map_err(|_| PasswordRulesError(format!("\"{}\" is not a number", value.trim())))
+}
+
+/// Extracts the host of a website entered as a URL or a bare domain.
+fn get_host(website: &str) -> Option<String> {
+    let website = website.trim().to_lowercase();
+    let url = if website.contains("://") {
+        Url::parse(&website).ok()?
+    } else {
+        Url::parse(&format!("https://{}", website)).ok()?
+    };
+    url.host_str().map(|host| host.to_string())
+}
+
+/// Looks up the rules of a website in the bundled database, trying the parent domains too
+/// so that "secure.example.com" finds the rules of "example.com".
+pub fn find_rules_for_website(website: &str) -> Option<String> {
+    let host = get_host(website)?;
+    let database: HashMap<String, PasswordRulesQuirk> =
+        serde_json::from_str(PASSWORD_RULES_DATABASE).ok()?;
+
+    let mut domain = host.as_str();
+    loop {
+        if let Some(quirk) = database.get(domain) {
+            return Some(quirk.password_rules.clone());
+        }
+        match domain.split_once('.') {
+            Some((_, parent)) if parent.contains('.') => domain = parent,
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let rules = PasswordRules::parse(
+            "minlength: 8; maxlength: 20; required: lower, upper; required: digit; allowed: [-_.]; max-consecutive: 2;",
+        )
+        .unwrap();
+        assert_eq!(rules.min_length, Some(8));
+        assert_eq!(rules.max_length, Some(20));
+        assert_eq!(rules.max_consecutive, Some(2));
+        assert_eq!(rules.required.len(), 2);
+        assert_eq!(rules.required[1], DIGIT.chars().collect::<Vec<char>>());
+        assert!(rules.allowed.contains(&'_'));
+        assert!(!rules.allowed.contains(&'!'));
+    }
+
+    #[test]
+    fn test_parse_custom_class_with_brackets() {
+        let rules = PasswordRules::parse("required: [-]]; allowed: [;,];").unwrap();
+        assert_eq!(rules.required[0], vec!['-', ']']);
+        assert!(rules.allowed.contains(&';'));
+        assert!(rules.allowed.contains(&','));
+
+        assert!(PasswordRules::parse("required: shiny").is_err());
+        assert!(PasswordRules::parse("minlength: 10; maxlength: 5").is_err());
+        assert!(PasswordRules::parse("required: [abc").is_err());
+        assert!(PasswordRules::parse("required: ;").is_err());
+        assert!(PasswordRules::parse("maxlength: 0").is_err());
+        assert!(PasswordRules::parse("minlength: 100000").is_err());
+        assert!(PasswordRules::parse("maxlength: 129").is_err());
+        assert!(PasswordRules::parse("required: upper; required: digit; maxlength: 1").is_err());
+        assert!(PasswordRules::parse("allowed: lower; required:").is_err());
+    }
+
+    #[test]
+    fn test_default_allowed() {
+        let rules = PasswordRules::parse("minlength: 4; unknown-property: 1").unwrap();
+        assert_eq!(rules.allowed.len(), 95);
+        assert!(rules.check("a b~").is_ok());
+        assert!(rules.check("abc").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let rules = PasswordRules::parse(
+            "required: upper; required: digit; allowed: lower; max-consecutive: 2",
+        )
+        .unwrap();
+        assert!(rules.check("Abc1").is_ok());
+        assert!(rules.check("abc1").is_err());
+        assert!(rules.check("Abbb1").is_err());
+    }
+
+    #[test]
+    fn test_find_rules_for_website() {
+        assert!(find_rules_for_website("https://secure.chase.com/login").is_some());
+        assert!(find_rules_for_website("Apple.com").is_some());
+        assert!(find_rules_for_website("unknown.example").is_none());
+        for (_, quirk) in
+            serde_json::from_str::<HashMap<String, PasswordRulesQuirk>>(PASSWORD_RULES_DATABASE)
+                .unwrap()
+        {
+            assert!(PasswordRules::parse(&quirk.password_rules).is_ok());
+        }
+    }
+}
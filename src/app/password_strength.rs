@@ -0,0 +1,103 @@
+use zxcvbn::zxcvbn;
+
+pub const MAX_SCORE: u8 = 4;
+
+/// The result of estimating how hard a password is to guess.
+#[derive(Clone, Debug)]
+pub struct PasswordStrength {
+    score: u8, // from 0 (guessable) to MAX_SCORE (very unguessable).
+    crack_time: String,
+    warning: Option<String>,
+    suggestions: Vec<String>,
+}
+
+impl PasswordStrength {
+    /// Estimates the strength of a password. It detects dictionary words, common passwords,
+    /// keyboard walks, sequences, repeats and dates, and words from `user_inputs` such as the
+    /// website or username.
+    pub fn estimate(password: &str, user_inputs: &[&str]) -> Self {
+        let entropy = zxcvbn(password, user_inputs);
+        let (warning, suggestions) = match entropy.feedback() {
+            Some(feedback) => (
+                feedback.warning().map(|warning| warning.to_string()),
+                feedback
+                    .suggestions()
+                    .iter()
+                    .map(|suggestion| suggestion.to_string())
+                    .collect(),
+            ),
+            None => (None, Vec::new()),
+        };
+
+        PasswordStrength {
+            score: entropy.score().into(),
+            // An offline attack against a slow hash, the same assumption zxcvbn's score uses.
+            crack_time: entropy
+                .crack_times()
+                .offline_slow_hashing_1e4_per_second()
+                .to_string(),
+            warning,
+            suggestions,
+        }
+    }
+
+    pub fn get_score(&self) -> u8 {
+        self.score
+    }
+
+    pub fn get_crack_time(&self) -> &str {
+        &self.crack_time
+    }
+
+    pub fn get_warning(&self) -> Option<&str> {
+        self.warning.as_deref()
+    }
+
+    pub fn get_suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        get_score_label(self.score)
+    }
+
+    /// Returns the warning, or else the first suggestion, as a single line of feedback.
+    pub fn get_feedback(&self) -> Option<&str> {
+        self.get_warning().or(self
+            .suggestions
+            .first()
+            .map(|suggestion| suggestion.as_str()))
+    }
+}
+
+pub fn get_score_label(score: u8) -> &'static str {
+    match score {
+        0 => "Very weak",
+        1 => "Weak",
+        2 => "Fair",
+        3 => "Strong",
+        _ => "Very strong",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate() {
+        let weak = PasswordStrength::estimate("password123", &[]);
+        assert_eq!(weak.get_score(), 0);
+        assert!(weak.get_feedback().is_some());
+
+        let walk = PasswordStrength::estimate("qwertyuiop", &[]);
+        assert!(walk.get_score() <= 1);
+
+        let own_name = PasswordStrength::estimate("keyloxsecret", &["keyloxsecret"]);
+        assert_eq!(own_name.get_score(), 0);
+
+        let strong = PasswordStrength::estimate("correct-horse-battery-staple-7", &[]);
+        assert_eq!(strong.get_score(), MAX_SCORE);
+        assert!(strong.get_warning().is_none());
+    }
+}
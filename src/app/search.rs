@@ -0,0 +1,213 @@
+use super::models::credentials::Credential;
+
+// The score of each matched character, in the manner of fzf.
+const SCORE_MATCH: i64 = 16;
+// A bonus for a match at the start of a word, such as the "g" of "github.com" or "me@gmail".
+const BONUS_BOUNDARY: i64 = 8;
+// A bonus for a match right after the previous one.
+const BONUS_CONSECUTIVE: i64 = 8;
+// The most a gap between two matched characters costs, so that a long note is still found.
+const MAX_GAP_PENALTY: i64 = 12;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum SearchFieldEnum {
+    Website,
+    Email,
+    Username,
+    Tags,
+    Notes,
+}
+
+impl SearchFieldEnum {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SearchFieldEnum::Website => "website",
+            SearchFieldEnum::Email => "email",
+            SearchFieldEnum::Username => "username",
+            SearchFieldEnum::Tags => "tag",
+            SearchFieldEnum::Notes => "notes",
+        }
+    }
+}
+
+/// The best match of the query in one credential.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub index: usize, // of the credential in the searched slice.
+    pub score: i64,
+    pub field: SearchFieldEnum,
+    pub text: String, // the matched field, or the matched tag or line of the notes.
+    pub positions: Vec<usize>, // the matched characters of `text`.
+}
+
+/// Fuzzy-matches the query against the website, email, username, tags and notes of the
+/// credentials with the given indices, ignoring case. The characters of the query have to
+/// appear in order in one field, one tag or one line of the notes. Returns the matches best
+/// first, then by website and email. An empty query matches every credential.
+pub fn search(
+    credentials: &[Credential],
+    query: &str,
+    indices: impl IntoIterator<Item = usize>,
+) -> Vec<SearchResult> {
+    let query: Vec<char> = query.trim().chars().map(to_lowercase).collect();
+
+    let mut results: Vec<SearchResult> = indices
+        .into_iter()
+        .filter_map(|index| match_credential(&credentials[index], index, &query))
+        .collect();
+    results.sort_by(|a, b| {
+        let (credential_a, credential_b) = (&credentials[a.index], &credentials[b.index]);
+        b.score
+            .cmp(&a.score)
+            .then(a.field.cmp(&b.field))
+            .then_with(|| credential_a.website.cmp(&credential_b.website))
+            .then_with(|| credential_a.email.cmp(&credential_b.email))
+    });
+    results
+}
+
+fn match_credential(credential: &Credential, index: usize, query: &[char]) -> Option<SearchResult> {
+    let texts = [
+        (SearchFieldEnum::Website, credential.website.as_str()),
+        (SearchFieldEnum::Email, credential.email.as_str()),
+        (SearchFieldEnum::Username, credential.username.as_str()),
+    ]
+    .into_iter()
+    .chain(
+        credential
+            .tags
+            .iter()
+            .map(|tag| (SearchFieldEnum::Tags, tag.as_str())),
+    )
+    .chain(
+        credential
+            .notes
+            .lines()
+            .map(|line| (SearchFieldEnum::Notes, line)),
+    );
+
+    let mut best: Option<SearchResult> = None;
+    for (field, text) in texts {
+        let (score, positions) = match match_text(text, query) {
+            Some(found) => found,
+            None => continue,
+        };
+        // The earlier field wins a tie.
+        if best.as_ref().is_none_or(|best| score > best.score) {
+            best = Some(SearchResult {
+                index,
+                score,
+                field,
+                text: text.to_string(),
+                positions,
+            });
+        }
+    }
+    best
+}
+
+/// Matches the query as a subsequence of the text. Returns the score and the positions of the
+/// matched characters.
+fn match_text(text: &str, query: &[char]) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    // The first match from the left ends the shortest window. Most texts fail here, before
+    // anything is allocated.
+    let mut query_index = 0;
+    let mut end = None;
+    for (position, c) in text.chars().enumerate() {
+        if to_lowercase(c) == query[query_index] {
+            query_index += 1;
+            if query_index == query.len() {
+                end = Some(position);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // Going back from the end finds the latest start, which keeps the window short.
+    let chars: Vec<char> = text.chars().take(end + 1).collect();
+    let mut positions = vec![0; query.len()];
+    let mut query_index = query.len();
+    for position in (0..=end).rev() {
+        if to_lowercase(chars[position]) == query[query_index - 1] {
+            query_index -= 1;
+            positions[query_index] = position;
+            if query_index == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut score = 0;
+    for (i, &position) in positions.iter().enumerate() {
+        score += SCORE_MATCH;
+        if position == 0 || !chars[position - 1].is_alphanumeric() {
+            score += BONUS_BOUNDARY;
+        }
+        if i > 0 {
+            let gap = (position - positions[i - 1] - 1) as i64;
+            score += if gap == 0 {
+                BONUS_CONSECUTIVE
+            } else {
+                -gap.min(MAX_GAP_PENALTY)
+            };
+        }
+    }
+    Some((score, positions))
+}
+
+fn to_lowercase(c: char) -> char {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase();
+    }
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_credential(website: &str, email: &str, tags: &[&str], notes: &str) -> Credential {
+        let mut credential = Credential::new(
+            Some(website.to_string()),
+            Some(email.to_string()),
+            None,
+            None,
+            Some(notes.to_string()),
+        );
+        credential.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        credential
+    }
+
+    #[test]
+    fn test_ranking() {
+        let credentials = vec![
+            get_credential("gitlab.com", "me@x.com", &[], ""),
+            get_credential("github.com", "me@x.com", &[], ""),
+            get_credential("bank.com", "me@x.com", &["work"], "Git hub recovery codes"),
+            get_credential("example.com", "me@x.com", &[], ""),
+        ];
+
+        let results = search(&credentials, "GitHub", 0..credentials.len());
+        let indices: Vec<usize> = results.iter().map(|result| result.index).collect();
+        assert_eq!(indices, vec![1, 2]);
+        assert_eq!(results[0].positions, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(results[1].field, SearchFieldEnum::Notes);
+        assert_eq!(results[1].positions, vec![0, 1, 2, 4, 5, 6]);
+
+        // Characters at word starts rank higher than characters closer together.
+        let results = search(&credentials, "gh", 0..credentials.len());
+        let indices: Vec<usize> = results.iter().map(|result| result.index).collect();
+        assert_eq!(indices, vec![2, 1]);
+
+        let results = search(&credentials, "wrk", [2, 3]);
+        assert_eq!(results[0].field, SearchFieldEnum::Tags);
+        assert_eq!(results[0].text, "work");
+
+        assert_eq!(search(&credentials, " ", 0..credentials.len()).len(), 4);
+    }
+}
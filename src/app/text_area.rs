@@ -0,0 +1,219 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::text_input::TextInput;
+
+/// Several lines of text being edited. Editing within a line works as in a `TextInput`, and the
+/// cursor also moves between lines.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct TextArea {
+    input: TextInput,
+    // The column, in graphemes, that Up and Down keep to across shorter lines.
+    preferred_column: Option<usize>,
+}
+
+impl TextArea {
+    pub fn new() -> Self {
+        TextArea::default()
+    }
+
+    pub fn get_value(&self) -> &str {
+        self.input.get_value()
+    }
+
+    /// Replaces the text, with the cursor at its end.
+    pub fn set_value(&mut self, value: &str) {
+        self.input.set_value(&value.replace("\r\n", "\n"));
+        self.preferred_column = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.preferred_column = None;
+    }
+
+    /// Returns the line editor for the edits that do not depend on lines, such as typing,
+    /// deleting and moving by character or word.
+    pub fn get_line_editor(&mut self) -> &mut TextInput {
+        self.preferred_column = None;
+        &mut self.input
+    }
+
+    pub fn insert_newline(&mut self) {
+        self.get_line_editor().insert_text("\n");
+    }
+
+    /// Inserts pasted text at the cursor, keeping its line breaks.
+    pub fn insert_str(&mut self, value: &str) {
+        let value: String = value
+            .replace("\r\n", "\n")
+            .replace('\t', "    ")
+            .chars()
+            .filter(|c| *c == '\n' || !c.is_control())
+            .collect();
+        self.get_line_editor().insert_text(&value);
+    }
+
+    pub fn move_line_start(&mut self) {
+        let line_start = self.get_line_start();
+        self.get_line_editor().set_cursor(line_start);
+    }
+
+    pub fn move_line_end(&mut self) {
+        let line_end = self.get_line_end();
+        self.get_line_editor().set_cursor(line_end);
+    }
+
+    pub fn delete_to_line_end(&mut self) {
+        let (cursor, line_end) = (self.input.get_cursor(), self.get_line_end());
+        let mut value = self.get_value().to_string();
+        value.replace_range(cursor..line_end, "");
+        self.input.set_value(&value);
+        self.get_line_editor().set_cursor(cursor);
+    }
+
+    /// Moves the cursor to the line above. Returns false on the first line.
+    pub fn move_up(&mut self) -> bool {
+        let line_start = self.get_line_start();
+        if line_start == 0 {
+            return false;
+        }
+        let column = self.get_column();
+        let previous_line_start = self.get_value()[..line_start - 1]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        self.move_to_column(previous_line_start, column);
+        true
+    }
+
+    /// Moves the cursor to the line below. Returns false on the last line.
+    pub fn move_down(&mut self) -> bool {
+        let line_end = self.get_line_end();
+        if line_end == self.get_value().len() {
+            return false;
+        }
+        let column = self.get_column();
+        self.move_to_column(line_end + 1, column);
+        true
+    }
+
+    /// Returns the rows that fit in `width` columns and `height` rows, with long lines wrapped,
+    /// and the column and row of the cursor in them. The rows scroll so that the cursor stays
+    /// visible.
+    pub fn get_view(&self, width: usize, height: usize) -> (Vec<String>, usize, usize) {
+        let width = width.max(1);
+        let cursor = self.input.get_cursor();
+        let mut rows = vec![String::new()];
+        let mut row_width = 0;
+        let mut cursor_position = None;
+        let mut offset = 0;
+
+        for grapheme in self.get_value().graphemes(true).chain(["\n"]) {
+            let is_line_end = grapheme == "\n";
+            let grapheme_width = if is_line_end { 0 } else { grapheme.width() };
+            // A cursor after a full row goes to the start of a row of its own.
+            let is_cursor = offset == cursor;
+            if row_width + grapheme_width > width || (is_cursor && row_width >= width) {
+                rows.push(String::new());
+                row_width = 0;
+            }
+            if is_cursor {
+                cursor_position = Some((row_width, rows.len() - 1));
+            }
+            offset += grapheme.len();
+
+            if is_line_end {
+                rows.push(String::new());
+                row_width = 0;
+            } else {
+                rows.last_mut().unwrap().push_str(grapheme);
+                row_width += grapheme_width;
+            }
+        }
+        // The row started by the line end that was added.
+        rows.pop();
+        let cursor_position = cursor_position.unwrap_or((0, 0));
+
+        let start = (cursor_position.1 + 1).saturating_sub(height.max(1));
+        let visible_rows = rows.into_iter().skip(start).take(height).collect();
+        (visible_rows, cursor_position.0, cursor_position.1 - start)
+    }
+
+    fn get_line_start(&self) -> usize {
+        self.get_value()[..self.input.get_cursor()]
+            .rfind('\n')
+            .map_or(0, |index| index + 1)
+    }
+
+    fn get_line_end(&self) -> usize {
+        let cursor = self.input.get_cursor();
+        self.get_value()[cursor..]
+            .find('\n')
+            .map_or(self.get_value().len(), |index| cursor + index)
+    }
+
+    fn get_column(&self) -> usize {
+        self.preferred_column.unwrap_or_else(|| {
+            self.get_value()[self.get_line_start()..self.input.get_cursor()]
+                .graphemes(true)
+                .count()
+        })
+    }
+
+    /// Moves the cursor to the column of the line that starts at `line_start`, or to the end
+    /// of a shorter line.
+    fn move_to_column(&mut self, line_start: usize, column: usize) {
+        let mut offset = line_start;
+        for grapheme in self.get_value()[line_start..].graphemes(true).take(column) {
+            if grapheme == "\n" {
+                break;
+            }
+            offset += grapheme.len();
+        }
+        self.input.set_cursor(offset);
+        self.preferred_column = Some(column);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let mut area = TextArea::new();
+        area.insert_str("first line\r\nab\nthird line");
+        assert!(!area.move_down());
+        assert!(area.move_up());
+        // The column is kept across the shorter line.
+        assert!(area.move_up());
+        area.get_line_editor().insert_char('|');
+        assert_eq!(area.get_value(), "first line|\nab\nthird line");
+
+        area.move_line_start();
+        assert!(area.move_down());
+        area.delete_to_line_end();
+        area.insert_newline();
+        assert_eq!(area.get_value(), "first line|\n\n\nthird line");
+        assert!(area.move_down());
+        assert!(!area.move_down());
+        area.move_line_end();
+        area.get_line_editor().insert_char('!');
+        assert_eq!(area.get_value(), "first line|\n\n\nthird line!");
+    }
+
+    #[test]
+    fn test_view() {
+        let mut area = TextArea::new();
+        area.set_value("abcdef\ngh");
+        assert_eq!(
+            area.get_view(4, 5),
+            (vec!["abcd".into(), "ef".into(), "gh".into()], 2, 2)
+        );
+        // Only the rows up to the cursor fit.
+        assert_eq!(area.get_view(4, 2), (vec!["ef".into(), "gh".into()], 2, 1));
+
+        area.set_value("abcd");
+        assert_eq!(area.get_view(4, 2), (vec!["abcd".into(), "".into()], 0, 1));
+    }
+}
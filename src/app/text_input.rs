@@ -0,0 +1,301 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// A single line of text being edited. The cursor is a byte offset that always sits between
+/// two graphemes, so that a character made of several code points is moved over and deleted
+/// as one.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct TextInput {
+    value: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        TextInput::default()
+    }
+
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub(super) fn get_cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the cursor to a byte offset, which has to be a grapheme boundary.
+    pub(super) fn set_cursor(&mut self, offset: usize) {
+        self.cursor = offset;
+    }
+
+    /// Replaces the text, with the cursor at its end. The text is kept as it is, so that a
+    /// stored value with line breaks is saved unchanged.
+    pub fn set_value(&mut self, value: &str) {
+        self.value = value.to_string();
+        self.cursor = self.value.len();
+    }
+
+    /// Returns the text and leaves the input empty.
+    pub fn take_value(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.value)
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    pub fn insert_char(&mut self, value: char) {
+        if value.is_control() {
+            return;
+        }
+        self.value.insert(self.cursor, value);
+        self.cursor += value.len_utf8();
+        // The character may join a grapheme with its neighbours, as a combining mark does.
+        self.cursor = self.get_next_boundary(self.cursor - value.len_utf8());
+    }
+
+    /// Inserts pasted text at the cursor. Line breaks at the end, as copied from a terminal or
+    /// a file, are dropped, and the lines of a longer text are joined.
+    pub fn insert_str(&mut self, value: &str) {
+        self.insert_text(&get_single_line(value.trim_end_matches(['\r', '\n'])));
+    }
+
+    /// Inserts the text at the cursor as it is.
+    pub(super) fn insert_text(&mut self, value: &str) {
+        self.value.insert_str(self.cursor, value);
+        self.cursor += value.len();
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.get_previous_boundary(self.cursor);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = self.get_next_boundary(self.cursor);
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.value.len();
+    }
+
+    pub fn move_word_left(&mut self) {
+        self.cursor = self.get_previous_word_start();
+    }
+
+    pub fn move_word_right(&mut self) {
+        self.cursor = self.get_next_word_end();
+    }
+
+    /// Deletes the grapheme before the cursor, like Backspace.
+    pub fn delete_before(&mut self) {
+        let start = self.get_previous_boundary(self.cursor);
+        self.value.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Deletes the grapheme after the cursor, like Delete.
+    pub fn delete_after(&mut self) {
+        let end = self.get_next_boundary(self.cursor);
+        self.value.replace_range(self.cursor..end, "");
+    }
+
+    /// Deletes the word before the cursor and the spaces after it, like Ctrl+W in a shell.
+    pub fn delete_word_before(&mut self) {
+        let start = self.get_previous_word_start();
+        self.value.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    pub fn delete_to_end(&mut self) {
+        self.value.truncate(self.cursor);
+    }
+
+    /// Returns the part of the text that fits in `width` columns and the column of the cursor
+    /// in it. The text scrolls horizontally so that the cursor stays visible. A masked text
+    /// shows one `*` per grapheme.
+    pub fn get_view(&self, width: usize, is_masked: bool) -> (String, usize) {
+        let graphemes: Vec<(usize, &str)> = self.value.grapheme_indices(true).collect();
+        let get_width = |grapheme: &str| match grapheme {
+            _ if is_masked => 1,
+            "\n" | "\r\n" => 1,
+            _ => grapheme.width(),
+        };
+        let cursor_index = graphemes
+            .iter()
+            .position(|(offset, _)| *offset >= self.cursor)
+            .unwrap_or(graphemes.len());
+
+        // The cursor needs a column of its own after the last grapheme.
+        let mut start = 0;
+        let mut cursor_column: usize = graphemes[..cursor_index]
+            .iter()
+            .map(|(_, grapheme)| get_width(grapheme))
+            .sum();
+        while cursor_column >= width && start < cursor_index {
+            cursor_column -= get_width(graphemes[start].1);
+            start += 1;
+        }
+
+        let mut view = String::new();
+        let mut view_width = 0;
+        for (_, grapheme) in &graphemes[start..] {
+            let grapheme_width = get_width(grapheme);
+            if view_width + grapheme_width > width {
+                break;
+            }
+            view.push_str(match *grapheme {
+                _ if is_masked => "*",
+                // A stored line break is shown, since a single line cannot break.
+                "\n" | "\r\n" => "↵",
+                _ => grapheme,
+            });
+            view_width += grapheme_width;
+        }
+        (view, cursor_column)
+    }
+
+    fn get_previous_boundary(&self, offset: usize) -> usize {
+        self.value[..offset]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn get_next_boundary(&self, offset: usize) -> usize {
+        self.value[offset..]
+            .graphemes(true)
+            .next()
+            .map_or(offset, |grapheme| offset + grapheme.len())
+    }
+
+    fn get_previous_word_start(&self) -> usize {
+        let mut offset = self.cursor;
+        let mut is_in_word = false;
+        for (index, grapheme) in self.value[..self.cursor].grapheme_indices(true).rev() {
+            let is_word = is_word_grapheme(grapheme);
+            if is_in_word && !is_word {
+                break;
+            }
+            is_in_word |= is_word;
+            offset = index;
+        }
+        offset
+    }
+
+    fn get_next_word_end(&self) -> usize {
+        let mut offset = self.cursor;
+        let mut is_in_word = false;
+        for grapheme in self.value[self.cursor..].graphemes(true) {
+            let is_word = is_word_grapheme(grapheme);
+            if is_in_word && !is_word {
+                break;
+            }
+            is_in_word |= is_word;
+            offset += grapheme.len();
+        }
+        offset
+    }
+}
+
+impl From<&str> for TextInput {
+    fn from(value: &str) -> Self {
+        let mut input = TextInput::new();
+        input.set_value(value);
+        input
+    }
+}
+
+fn is_word_grapheme(grapheme: &str) -> bool {
+    grapheme.chars().any(char::is_alphanumeric)
+}
+
+/// Joins the lines of the text with spaces and drops other control characters.
+fn get_single_line(value: &str) -> String {
+    value
+        .lines()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing() {
+        let mut input = TextInput::from("hunter2");
+        input.move_left();
+        input.insert_char('X');
+        assert_eq!(input.get_value(), "hunterX2");
+        input.move_home();
+        input.delete_after();
+        input.move_end();
+        input.delete_before();
+        assert_eq!(input.get_value(), "unterX");
+
+        input.set_value("correct horse  battery");
+        input.delete_word_before();
+        assert_eq!(input.get_value(), "correct horse  ");
+        input.delete_word_before();
+        assert_eq!(input.get_value(), "correct ");
+        input.move_word_left();
+        input.move_word_right();
+        input.delete_to_end();
+        assert_eq!(input.get_value(), "correct");
+        input.move_word_left();
+        input.insert_char('_');
+        assert_eq!(input.get_value(), "_correct");
+
+        input.clear();
+        input.insert_str("pass\nword\r\n");
+        assert_eq!(input.get_value(), "pass word");
+    }
+
+    #[test]
+    fn test_graphemes() {
+        // "e" with a combining acute accent, and a family emoji joined with zero-width joiners.
+        let mut input = TextInput::from("ae\u{301}\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}b");
+        input.move_left();
+        input.move_left();
+        input.delete_before();
+        assert_eq!(
+            input.get_value(),
+            "a\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}b"
+        );
+        input.delete_after();
+        assert_eq!(input.get_value(), "ab");
+
+        input.set_value("e");
+        input.insert_char('\u{301}');
+        input.move_left();
+        input.insert_char('_');
+        assert_eq!(input.get_value(), "_e\u{301}");
+    }
+
+    #[test]
+    fn test_view() {
+        let mut input = TextInput::from("abcdefgh");
+        assert_eq!(input.get_view(5, false), ("efgh".to_string(), 4));
+        input.move_home();
+        assert_eq!(input.get_view(5, false), ("abcde".to_string(), 0));
+        assert_eq!(input.get_view(5, true), ("*****".to_string(), 0));
+
+        // Wide characters take two columns.
+        input.set_value("日本語");
+        assert_eq!(input.get_view(5, false), ("本語".to_string(), 4));
+        assert_eq!(input.get_view(5, true), ("***".to_string(), 3));
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// The EFF large wordlist: 7776 words, one per roll of five dice.
+/// https://www.eff.org/deeplinks/2016/07/new-wordlists-random-passphrases
+const EFF_LARGE_WORDLIST: &str = include_str!("wordlists/eff_large_wordlist.txt");
+
+/// Custom wordlists need enough words for the passphrase entropy to be meaningful.
+pub const MIN_WORDLIST_SIZE: usize = 100;
+
+// The generator reads the wordlist on every keystroke and redraw, so the embedded list is
+// parsed once and the last custom list is kept until its path changes.
+static EFF_WORDLIST: OnceLock<Arc<Vec<String>>> = OnceLock::new();
+static CUSTOM_WORDLIST: Mutex<Option<CachedWordlist>> = Mutex::new(None);
+
+struct CachedWordlist {
+    path: String,
+    words: Result<Arc<Vec<String>>, String>,
+}
+
+/// Parses a wordlist with one word per line. Lines in the diceware format ("11111 word")
+/// are accepted, empty lines and lines starting with '#' are skipped and duplicates dropped.
+pub fn parse_wordlist(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_whitespace().last())
+        .filter(|word| seen.insert(word.to_string()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Loads the custom wordlist at `path`, or the embedded EFF wordlist when `path` is empty.
+/// A custom wordlist is read again only once the path changes.
+pub fn load_wordlist(path: &str) -> Result<Arc<Vec<String>>, Box<dyn Error>> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Ok(EFF_WORDLIST
+            .get_or_init(|| Arc::new(parse_wordlist(EFF_LARGE_WORDLIST)))
+            .clone());
+    }
+
+    let mut cached = CUSTOM_WORDLIST.lock().unwrap();
+    if cached.as_ref().is_none_or(|cached| cached.path != path) {
+        *cached = Some(CachedWordlist {
+            path: path.to_string(),
+            words: read_wordlist(path).map(Arc::new),
+        });
+    }
+    match &cached.as_ref().unwrap().words {
+        Ok(words) => Ok(words.clone()),
+        Err(err) => Err(err.clone().into()),
+    }
+}
+
+fn read_wordlist(path: &str) -> Result<Vec<String>, String> {
+    let content = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let words = parse_wordlist(&content);
+    if words.len() < MIN_WORDLIST_SIZE {
+        return Err(format!(
+            "{} has {} words, at least {} are required",
+            path,
+            words.len(),
+            MIN_WORDLIST_SIZE
+        ));
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eff_wordlist() {
+        let words = load_wordlist("").unwrap();
+        assert_eq!(words.len(), 7776);
+        assert_eq!(words[0], "abacus");
+        assert_eq!(words[7775], "zoom");
+        assert!(Arc::ptr_eq(&words, &load_wordlist(" ").unwrap()));
+    }
+
+    #[test]
+    fn test_parse_wordlist() {
+        let words = parse_wordlist("# comment\n11111\tapple\n\nbanana\n  cherry  \napple\n");
+        assert_eq!(words, vec!["apple", "banana", "cherry"]);
+    }
+}
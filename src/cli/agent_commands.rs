@@ -0,0 +1,193 @@
+use std::env;
+use std::error::Error;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use crate::agent::{
+    self, client,
+    protocol::{AgentStatus, HexKey, RequestEnum, ResponseEnum},
+};
+use crate::app::credentials_storage;
+
+use super::commands;
+use super::output;
+use super::password_source::PasswordArgs;
+use super::{AgentCommandEnum, CliError, FormatEnum, TimeoutArgs};
+
+// How long `start` waits for the agent to listen.
+const START_TIMEOUT: Duration = Duration::from_secs(5);
+const START_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub fn run(
+    command: AgentCommandEnum,
+    password_args: &PasswordArgs,
+    format: FormatEnum,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        AgentCommandEnum::Start { timeouts } => start(timeouts),
+        AgentCommandEnum::Run { timeouts } => Ok(agent::server::run(
+            &agent::get_socket_path(),
+            timeouts.idle_timeout.unwrap_or(agent::DEFAULT_IDLE_TIMEOUT),
+            timeouts.max_lifetime.unwrap_or(agent::DEFAULT_MAX_LIFETIME),
+        )?),
+        AgentCommandEnum::Stop => {
+            send(&RequestEnum::Stop)?;
+            eprintln!("Agent stopped");
+            Ok(())
+        }
+        AgentCommandEnum::Unlock => unlock(password_args, format),
+        AgentCommandEnum::Lock => {
+            send(&RequestEnum::Lock)?;
+            eprintln!("The agent dropped the key");
+            Ok(())
+        }
+        AgentCommandEnum::Status => print_status(get_status()?, format),
+        AgentCommandEnum::Timeout { timeouts } => {
+            let response = send(&RequestEnum::SetTimeouts {
+                idle_timeout_seconds: timeouts.idle_timeout.map(|timeout| timeout.as_secs()),
+                max_lifetime_seconds: timeouts.max_lifetime.map(|lifetime| lifetime.as_secs()),
+            })?;
+            match response {
+                ResponseEnum::Status(status) => print_status(status, format),
+                _ => Err(unexpected_response()),
+            }
+        }
+    }
+}
+
+/// Errors of an agent that is not running have their own exit code.
+fn send(request: &RequestEnum) -> Result<ResponseEnum, CliError> {
+    if !client::is_running() {
+        return Err(CliError::NotFound(format!(
+            "No agent is running on {}, start one with \"KeyLox agent start\"",
+            agent::get_socket_path().display()
+        )));
+    }
+    client::send(request).map_err(|err| CliError::Failed(err.to_string()))
+}
+
+fn unexpected_response() -> Box<dyn Error> {
+    Box::new(CliError::Failed(
+        "Unexpected response from the agent".to_string(),
+    ))
+}
+
+/// Starts `agent run` as a detached process and waits until it listens.
+fn start(timeouts: TimeoutArgs) -> Result<(), Box<dyn Error>> {
+    let socket_path = agent::get_socket_path();
+    if client::is_running() {
+        return Err(Box::new(CliError::Failed(format!(
+            "An agent is already running on {}",
+            socket_path.display()
+        ))));
+    }
+
+    let mut command = Command::new(env::current_exe()?);
+    command.args(["agent", "run"]);
+    if let Some(idle_timeout) = timeouts.idle_timeout {
+        command.args(["--idle-timeout", &idle_timeout.as_secs().to_string()]);
+    }
+    if let Some(max_lifetime) = timeouts.max_lifetime {
+        command.args(["--max-lifetime", &max_lifetime.as_secs().to_string()]);
+    }
+    // Its own process group keeps Ctrl+C in the terminal from stopping it.
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+
+    let mut waited = Duration::ZERO;
+    while waited < START_TIMEOUT {
+        if client::is_running() {
+            eprintln!(
+                "Agent started (pid {}), listening on {}",
+                child.id(),
+                socket_path.display()
+            );
+            return Ok(());
+        }
+        if child.try_wait()?.is_some() {
+            let output = child.wait_with_output()?;
+            return Err(Box::new(CliError::Failed(format!(
+                "The agent did not start: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))));
+        }
+        thread::sleep(START_POLL_INTERVAL);
+        waited += START_POLL_INTERVAL;
+    }
+    Err(Box::new(CliError::Failed(
+        "The agent did not start in time".to_string(),
+    )))
+}
+
+/// Opens the vault with the master password and hands its key to the agent.
+fn unlock(password_args: &PasswordArgs, format: FormatEnum) -> Result<(), Box<dyn Error>> {
+    // Checked first so that the slow key derivation is not wasted.
+    send(&RequestEnum::Status)?;
+
+    let app = commands::unlock_with_password(password_args)?;
+    send(&RequestEnum::Unlock {
+        vault: credentials_storage::get_vault_path()?,
+        key: HexKey::encode(&app.master_key),
+    })?;
+    print_status(get_status()?, format)
+}
+
+fn get_status() -> Result<AgentStatus, Box<dyn Error>> {
+    match send(&RequestEnum::Status)? {
+        ResponseEnum::Status(status) => Ok(status),
+        _ => Err(unexpected_response()),
+    }
+}
+
+fn print_status(status: AgentStatus, format: FormatEnum) -> Result<(), Box<dyn Error>> {
+    if format == FormatEnum::Json {
+        println!("{}", output::format_agent_status(&status));
+        return Ok(());
+    }
+
+    println!(
+        "Agent running (pid {}) on {}",
+        status.pid,
+        agent::get_socket_path().display()
+    );
+    match &status.vault {
+        Some(vault) => println!("Unlocked: {}", vault.display()),
+        None => println!("Locked"),
+    }
+    let format_limit = |seconds: u64, remaining_seconds: Option<u64>| {
+        let limit = agent::format_duration(Duration::from_secs(seconds));
+        match remaining_seconds {
+            Some(remaining) => format!("{} ({} left)", limit, format_remaining(remaining)),
+            None => limit,
+        }
+    };
+    println!(
+        "Idle timeout: {}",
+        format_limit(status.idle_timeout_seconds, status.idle_remaining_seconds)
+    );
+    println!(
+        "Maximum lifetime: {}",
+        format_limit(
+            status.max_lifetime_seconds,
+            status.lifetime_remaining_seconds
+        )
+    );
+    if status.vault.is_some() && !status.is_memory_locked {
+        println!("Warning: the key could not be locked in memory and may be swapped to disk");
+    }
+    Ok(())
+}
+
+fn format_remaining(seconds: u64) -> String {
+    match seconds {
+        3600.. => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+        60.. => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!("{}s", seconds),
+    }
+}
@@ -0,0 +1,436 @@
+use crate::app::{
+    app::App,
+    breach_check, credentials_storage,
+    health::HealthReport,
+    otp,
+    password_generator::{self, PasswordGeneratorModeEnum, PasswordGeneratorSettings},
+    password_rules::PasswordRules,
+};
+
+use super::output::{self, EntryOutput, SearchResultOutput};
+use super::password_source::{self, PasswordArgs};
+use super::{CliError, EntryFields, FieldEnum, FormatEnum};
+
+use chrono::Utc;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Opens the vault with the key cached in the kernel keyring or held by the agent, and
+/// otherwise with the master password from the chosen source.
+fn unlock(password_args: &PasswordArgs) -> Result<App, Box<dyn Error>> {
+    let mut app = open_app()?;
+    if app.load_credentials_from_keyring() {
+        print_status_message(&mut app);
+        return Ok(app);
+    }
+    #[cfg(unix)]
+    if crate::agent::client::unlock_app(&mut app) {
+        print_status_message(&mut app);
+        return Ok(app);
+    }
+    load_with_password(&mut app, password_args)?;
+    Ok(app)
+}
+
+/// Opens the vault with the master password even when the agent could open it.
+pub fn unlock_with_password(password_args: &PasswordArgs) -> Result<App, Box<dyn Error>> {
+    let mut app = open_app()?;
+    load_with_password(&mut app, password_args)?;
+    Ok(app)
+}
+
+fn open_app() -> Result<App, Box<dyn Error>> {
+    if !credentials_storage::are_credentials_present() {
+        return Err(Box::new(CliError::NotFound(
+            "No vault found in the current directory, run KeyLox without a command to create one"
+                .to_string(),
+        )));
+    }
+
+    let mut app = App::new();
+    print_status_message(&mut app);
+    Ok(app)
+}
+
+fn load_with_password(app: &mut App, password_args: &PasswordArgs) -> Result<(), Box<dyn Error>> {
+    let password = password_source::read_master_password(password_args)?;
+    app.load_credentials(&password)?;
+    app.cache_master_key();
+    print_status_message(app);
+    Ok(())
+}
+
+/// Messages the terminal interface would show in its footer go to stderr.
+fn print_status_message(app: &mut App) {
+    if let Some(message) = app.status_message.take() {
+        eprintln!("{}", message);
+    }
+}
+
+/// Finds an entry by website and email. Both match case-insensitively when there is no exact
+/// match, and the email can be left out when the website has a single entry.
+fn find_entry(app: &App, website: &str, email: Option<&str>) -> Result<(String, String), CliError> {
+    let websites = app.credentials.get_websites();
+    let website = find_match(&websites, website)
+        .ok_or_else(|| CliError::NotFound(format!("No entry for {}", website)))?;
+
+    let emails = app.credentials.get_emails(&website);
+    let email = match email {
+        Some(email) => find_match(&emails, email)
+            .ok_or_else(|| CliError::NotFound(format!("No entry for {} / {}", website, email)))?,
+        None if emails.len() == 1 => emails[0].clone(),
+        None => {
+            return Err(CliError::Failed(format!(
+                "{} has several entries, pick one with --email: {}",
+                website,
+                emails.join(", ")
+            )))
+        }
+    };
+
+    Ok((website, email))
+}
+
+fn find_match(values: &[String], value: &str) -> Option<String> {
+    if let Some(exact) = values.iter().find(|v| *v == value) {
+        return Some(exact.clone());
+    }
+    let mut matches = values.iter().filter(|v| v.eq_ignore_ascii_case(value));
+    match (matches.next(), matches.next()) {
+        (Some(only), None) => Some(only.clone()),
+        _ => None,
+    }
+}
+
+/// Describes an entry for the JSON output. Secrets are left out unless `show_secrets` is set.
+fn get_entry_output(
+    app: &App,
+    website: &str,
+    email: &str,
+    show_secrets: bool,
+) -> Result<EntryOutput, CliError> {
+    let credential = app
+        .credentials
+        .get_credential(website, email)
+        .ok_or_else(|| CliError::NotFound(format!("No entry for {} / {}", website, email)))?;
+
+    Ok(EntryOutput {
+        has_otp: credential.otp.is_some(),
+        attachment_count: credential.attachments.len(),
+        created_at: credential.created_at,
+        modified_at: credential.modified_at,
+        password_modified_at: credential.password_modified_at,
+        last_accessed_at: credential.last_accessed_at,
+        password: show_secrets.then(|| credential.password.clone()),
+        notes: show_secrets.then(|| credential.notes.clone()),
+        otp_code: match &credential.otp {
+            Some(params) if show_secrets => Some(otp::generate_code(params, otp::get_unix_time())),
+            _ => None,
+        },
+        website: credential.website,
+        email: credential.email,
+        username: credential.username,
+        tags: credential.tags,
+    })
+}
+
+pub fn list(
+    password_args: &PasswordArgs,
+    website: Option<&str>,
+    format: FormatEnum,
+) -> Result<(), Box<dyn Error>> {
+    let app = unlock(password_args)?;
+
+    let websites = match website {
+        Some(website) => vec![find_match(&app.credentials.get_websites(), website)
+            .ok_or_else(|| CliError::NotFound(format!("No entry for {}", website)))?],
+        None => app.websites.clone(),
+    };
+    let mut entries = Vec::new();
+    for website in websites {
+        for email in app.credentials.get_emails(&website) {
+            entries.push(get_entry_output(&app, &website, &email, false)?);
+        }
+    }
+
+    match format {
+        FormatEnum::Text => {
+            for entry in &entries {
+                println!("{}\t{}", entry.website, entry.email);
+            }
+        }
+        FormatEnum::Json => println!("{}", output::format_entries(&entries)),
+    }
+    Ok(())
+}
+
+pub fn search(
+    password_args: &PasswordArgs,
+    query: &str,
+    format: FormatEnum,
+    show_secrets: bool,
+) -> Result<(), Box<dyn Error>> {
+    let app = unlock(password_args)?;
+    let query_lowercase = query.to_lowercase();
+    let contains = |value: &str| value.to_lowercase().contains(&query_lowercase);
+
+    let mut results = Vec::new();
+    for website in &app.websites {
+        for email in app.credentials.get_emails(website) {
+            let entry = get_entry_output(&app, website, &email, show_secrets)?;
+            let mut matched_fields = Vec::new();
+            if contains(&entry.website) {
+                matched_fields.push("website");
+            }
+            if contains(&entry.email) {
+                matched_fields.push("email");
+            }
+            if contains(&entry.username) {
+                matched_fields.push("username");
+            }
+            if entry.tags.iter().any(|tag| contains(tag)) {
+                matched_fields.push("tags");
+            }
+            if !matched_fields.is_empty() {
+                results.push(SearchResultOutput {
+                    entry,
+                    matched_fields,
+                });
+            }
+        }
+    }
+
+    match format {
+        FormatEnum::Text => {
+            for result in &results {
+                println!("{}\t{}", result.entry.website, result.entry.email);
+            }
+        }
+        FormatEnum::Json => println!("{}", output::format_search_results(query, &results)),
+    }
+    if results.is_empty() {
+        return Err(Box::new(CliError::NotFound(format!(
+            "No entry matches \"{}\"",
+            query
+        ))));
+    }
+    Ok(())
+}
+
+pub fn get(
+    password_args: &PasswordArgs,
+    website: &str,
+    email: Option<&str>,
+    field: FieldEnum,
+    format: FormatEnum,
+    show_secrets: bool,
+) -> Result<(), Box<dyn Error>> {
+    let app = unlock(password_args)?;
+    let (website, email) = find_entry(&app, website, email)?;
+    if format == FormatEnum::Json {
+        let entry = get_entry_output(&app, &website, &email, show_secrets)?;
+        println!("{}", output::format_entry(&entry));
+        return Ok(());
+    }
+
+    let credential = app
+        .credentials
+        .get_credential(&website, &email)
+        .ok_or_else(|| CliError::NotFound(format!("No entry for {} / {}", website, email)))?;
+
+    let value = match field {
+        FieldEnum::Password => credential.password,
+        FieldEnum::Username => credential.username,
+        FieldEnum::Email => credential.email,
+        FieldEnum::Website => credential.website,
+        FieldEnum::Notes => credential.notes,
+        FieldEnum::Tags => credential.tags.join(", "),
+        FieldEnum::Otp => match &credential.otp {
+            Some(params) => otp::generate_code(params, otp::get_unix_time()),
+            None => {
+                return Err(Box::new(CliError::NotFound(format!(
+                    "{} / {} has no OTP",
+                    website, email
+                ))))
+            }
+        },
+    };
+    println!("{}", value);
+    Ok(())
+}
+
+/// Puts the given fields in the editor inputs, so that saving validates them the same way the
+/// terminal interface does. Returns the generated password, if one was generated.
+fn apply_fields(app: &mut App, fields: EntryFields) -> Result<Option<String>, CliError> {
+    if let Some(username) = fields.username {
+        app.username_input.set_value(&username);
+    }
+    if let Some(password) = fields.password {
+        app.password_input.set_value(&password);
+    }
+    if let Some(notes) = fields.notes {
+        app.notes_input.set_value(&notes);
+    }
+    if let Some(tags) = fields.tags {
+        app.tags_input.set_value(&tags);
+    }
+    if let Some(otp) = fields.otp {
+        app.otp_input.set_value(&otp);
+    }
+
+    if !fields.generate {
+        return Ok(None);
+    }
+    app.regenerate_password();
+    if app.generated_password.is_empty() {
+        let message = app.status_message.take().unwrap_or_default();
+        return Err(CliError::Failed(format!(
+            "Could not generate a password: {}",
+            message
+        )));
+    }
+    let password = std::mem::take(&mut app.generated_password);
+    app.password_input.set_value(&password);
+    Ok(Some(password))
+}
+
+pub fn add(
+    password_args: &PasswordArgs,
+    website: &str,
+    email: &str,
+    fields: EntryFields,
+) -> Result<(), Box<dyn Error>> {
+    let mut app = unlock(password_args)?;
+    if app.credentials.get_credential(website, email).is_some() {
+        return Err(Box::new(CliError::Failed(format!(
+            "{} / {} already exists, use edit to change it",
+            website, email
+        ))));
+    }
+
+    app.website_input.set_value(website);
+    app.email_input.set_value(email);
+    app.suggest_password_rules();
+    print_status_message(&mut app);
+
+    let prompt_password = fields.password.is_none() && !fields.generate;
+    let generated_password = apply_fields(&mut app, fields)?;
+    if prompt_password {
+        let password = rpassword::prompt_password(format!("Password for {}: ", website))?;
+        app.password_input.set_value(&password);
+    }
+
+    app.save_credential()?;
+    app.save_changes()?;
+    if let Some(password) = generated_password {
+        println!("{}", password);
+    }
+    eprintln!("Added {} / {}", website, email);
+    Ok(())
+}
+
+pub fn edit(
+    password_args: &PasswordArgs,
+    website: &str,
+    email: Option<&str>,
+    fields: EntryFields,
+) -> Result<(), Box<dyn Error>> {
+    let mut app = unlock(password_args)?;
+    let (website, email) = find_entry(&app, website, email)?;
+    app.select_credential(&website, &email);
+    app.load_credential();
+
+    let generated_password = apply_fields(&mut app, fields)?;
+    app.save_credential()?;
+    app.save_changes()?;
+    if let Some(password) = generated_password {
+        println!("{}", password);
+    }
+    eprintln!("Updated {} / {}", website, email);
+    Ok(())
+}
+
+pub fn remove(
+    password_args: &PasswordArgs,
+    website: &str,
+    email: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let mut app = unlock(password_args)?;
+    let (website, email) = find_entry(&app, website, email)?;
+    app.credentials.move_to_trash(&website, &email);
+    app.save_changes()?;
+    eprintln!("Moved {} / {} to the trash", website, email);
+    Ok(())
+}
+
+pub fn generate(
+    length: Option<usize>,
+    passphrase: bool,
+    words: Option<u32>,
+    no_symbols: bool,
+    rules: Option<&str>,
+) -> Result<(), Box<dyn Error>> {
+    let mut settings = PasswordGeneratorSettings::default();
+    if passphrase {
+        settings.mode = PasswordGeneratorModeEnum::Passphrase;
+    }
+    if let Some(length) = length {
+        settings.length = length;
+    }
+    if let Some(words) = words {
+        settings.word_count = words as usize;
+    }
+    if no_symbols {
+        settings.use_symbols = false;
+    }
+    let rules = match rules {
+        Some(rules) => Some(PasswordRules::parse(rules)?),
+        None => None,
+    };
+
+    println!(
+        "{}",
+        password_generator::generate_password(&settings, rules.as_ref())?
+    );
+    Ok(())
+}
+
+pub fn audit(
+    password_args: &PasswordArgs,
+    breach_list: Option<PathBuf>,
+    format: FormatEnum,
+) -> Result<(), Box<dyn Error>> {
+    let app = unlock(password_args)?;
+    // The breach list of config.json was already checked when the vault was opened.
+    let breach_counts = match breach_list {
+        Some(path) => breach_check::get_breach_counts(&app.credentials, &path)?,
+        None => app.breach_counts.clone(),
+    };
+
+    let report = HealthReport::analyze(&app.credentials, &breach_counts, Utc::now());
+    match format {
+        FormatEnum::Text => print!("{}", report.to_text()),
+        FormatEnum::Json => println!("{}", output::format_audit(&report)),
+    }
+    Ok(())
+}
+
+pub fn build_breach_index(source: &Path, destination: &Path) -> Result<(), Box<dyn Error>> {
+    let record_count = breach_check::build_index(source, destination)?;
+    eprintln!("Wrote {} hashes to {}", record_count, destination.display());
+    Ok(())
+}
+
+/// Revokes the key cached in the kernel keyring and makes the agent drop its key.
+pub fn lock() -> Result<(), Box<dyn Error>> {
+    if App::revoke_cached_master_key()? {
+        eprintln!("Revoked the key in the kernel keyring");
+    }
+    #[cfg(unix)]
+    if crate::agent::client::is_running() {
+        crate::agent::client::send(&crate::agent::protocol::RequestEnum::Lock)?;
+        eprintln!("The agent dropped the key");
+    }
+    eprintln!("Locked");
+    Ok(())
+}
@@ -0,0 +1,322 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::time::Duration;
+
+use crate::app::password_generator::MAX_WORD_COUNT;
+use crate::app::vault_encryptor::VaultError;
+
+#[cfg(unix)]
+mod agent_commands;
+mod commands;
+mod output;
+mod password_source;
+
+pub use password_source::PasswordArgs;
+
+// Exit codes. Invalid arguments exit with 2, as clap does.
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_WRONG_PASSWORD: i32 = 3;
+pub const EXIT_NOT_FOUND: i32 = 4;
+pub const EXIT_LOCKED: i32 = 5;
+pub const EXIT_CORRUPTED: i32 = 6;
+
+/// KeyLox starts the terminal interface when no command is given. The commands work on the
+/// vault in the current directory without it, for scripts and CI jobs.
+#[derive(Parser)]
+#[command(name = "KeyLox", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CommandEnum>,
+    /// The output of list, get, search and audit, and of errors
+    #[arg(long, global = true, value_enum, default_value_t = FormatEnum::Text)]
+    pub format: FormatEnum,
+    /// Includes passwords, notes and OTP codes in JSON output
+    #[arg(long, global = true)]
+    pub show_secrets: bool,
+    #[command(flatten)]
+    pub password: PasswordArgs,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum FormatEnum {
+    Text,
+    /// A versioned schema, see the README
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum CommandEnum {
+    /// Lists the entries of the vault, or of one website
+    List { website: Option<String> },
+    /// Finds entries whose website, email, username or tags contain the query
+    Search { query: String },
+    /// Prints one field of an entry, or the whole entry as JSON
+    Get {
+        website: String,
+        /// Needed when the website has several entries
+        #[arg(long)]
+        email: Option<String>,
+        #[arg(long, value_enum, default_value_t = FieldEnum::Password)]
+        field: FieldEnum,
+    },
+    /// Adds an entry. The password is prompted for unless it is given or generated
+    Add {
+        website: String,
+        #[arg(long)]
+        email: String,
+        #[command(flatten)]
+        fields: EntryFields,
+    },
+    /// Changes the given fields of an entry
+    Edit {
+        website: String,
+        #[arg(long)]
+        email: Option<String>,
+        #[command(flatten)]
+        fields: EntryFields,
+    },
+    /// Moves an entry to the trash
+    Rm {
+        website: String,
+        #[arg(long)]
+        email: Option<String>,
+    },
+    /// Prints a generated password without opening the vault
+    Generate {
+        #[arg(long)]
+        length: Option<usize>,
+        /// Generates a diceware passphrase instead
+        #[arg(long)]
+        passphrase: bool,
+        /// The number of words of a passphrase
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=MAX_WORD_COUNT as i64))]
+        words: Option<u32>,
+        #[arg(long)]
+        no_symbols: bool,
+        /// Password rules in Apple's passwordrules syntax
+        #[arg(long)]
+        rules: Option<String>,
+    },
+    /// Prints the health report of the vault
+    Audit {
+        /// A pwned-passwords list, instead of the one in config.json
+        #[arg(long)]
+        breach_list: Option<PathBuf>,
+    },
+    /// Builds a compact breach list index from the Have I Been Pwned text download
+    BreachIndex {
+        source: PathBuf,
+        destination: PathBuf,
+    },
+    /// Revokes the key cached in the kernel keyring and makes the agent drop its key
+    Lock,
+    /// Keeps the vault unlocked for later commands and the terminal interface
+    #[cfg(unix)]
+    Agent {
+        #[command(subcommand)]
+        command: AgentCommandEnum,
+    },
+}
+
+#[cfg(unix)]
+#[derive(Subcommand)]
+pub enum AgentCommandEnum {
+    /// Starts the agent in the background
+    Start {
+        #[command(flatten)]
+        timeouts: TimeoutArgs,
+    },
+    /// Runs the agent in the foreground, for a service manager
+    Run {
+        #[command(flatten)]
+        timeouts: TimeoutArgs,
+    },
+    /// Stops the agent, which drops the key
+    Stop,
+    /// Opens the vault in the current directory and hands its key to the agent
+    Unlock,
+    /// Makes the agent drop the key
+    Lock,
+    /// Shows whether the agent holds a key and for how long it keeps it
+    Status,
+    /// Changes the timeouts of the running agent
+    Timeout {
+        #[command(flatten)]
+        timeouts: TimeoutArgs,
+    },
+}
+
+#[cfg(unix)]
+#[derive(clap::Args)]
+pub struct TimeoutArgs {
+    /// Drops the key when it was not used for this long, such as 15m. 0 keeps it
+    #[arg(long, value_parser = crate::agent::parse_duration)]
+    pub idle_timeout: Option<Duration>,
+    /// Drops the key this long after the unlock, such as 8h. 0 keeps it
+    #[arg(long, value_parser = crate::agent::parse_duration)]
+    pub max_lifetime: Option<Duration>,
+}
+
+#[derive(clap::Args)]
+pub struct EntryFields {
+    #[arg(long)]
+    pub username: Option<String>,
+    /// Visible to other users in the process list, prefer the prompt or --generate
+    #[arg(long, conflicts_with = "generate")]
+    pub password: Option<String>,
+    /// Generates the password with the vault's generator settings and the entry's rules
+    #[arg(long)]
+    pub generate: bool,
+    #[arg(long)]
+    pub notes: Option<String>,
+    /// Comma-separated tags
+    #[arg(long)]
+    pub tags: Option<String>,
+    /// A base32 secret or an otpauth:// URI
+    #[arg(long)]
+    pub otp: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum FieldEnum {
+    Password,
+    Username,
+    Email,
+    Website,
+    Notes,
+    Tags,
+    /// The current one-time code
+    Otp,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    WrongPassword,
+    NotFound(String),
+    // The master password is needed but cannot be asked for.
+    Locked(String),
+    Corrupted(String),
+    Failed(String),
+}
+
+impl CliError {
+    fn from_error(err: Box<dyn Error>) -> Self {
+        let err = match err.downcast::<CliError>() {
+            Ok(err) => return *err,
+            Err(err) => err,
+        };
+        match err.downcast::<VaultError>() {
+            Ok(err) => match *err {
+                VaultError::WrongPassword => CliError::WrongPassword,
+                VaultError::Corrupted(reason) => CliError::Corrupted(reason),
+            },
+            Err(err) => CliError::Failed(err.to_string()),
+        }
+    }
+
+    pub fn get_exit_code(&self) -> i32 {
+        match self {
+            CliError::WrongPassword => EXIT_WRONG_PASSWORD,
+            CliError::NotFound(_) => EXIT_NOT_FOUND,
+            CliError::Locked(_) => EXIT_LOCKED,
+            CliError::Corrupted(_) => EXIT_CORRUPTED,
+            CliError::Failed(_) => EXIT_FAILED,
+        }
+    }
+
+    /// The error code of the JSON output.
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            CliError::WrongPassword => "wrong_password",
+            CliError::NotFound(_) => "not_found",
+            CliError::Locked(_) => "locked",
+            CliError::Corrupted(_) => "corrupted",
+            CliError::Failed(_) => "failed",
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::WrongPassword => write!(f, "Wrong master password"),
+            CliError::Corrupted(reason) => write!(f, "The vault is corrupted: {}", reason),
+            CliError::NotFound(message) | CliError::Locked(message) | CliError::Failed(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl Error for CliError {}
+
+/// Runs a command and returns the exit code. Errors are printed to stderr in the chosen format.
+pub fn run(cli: Cli) -> i32 {
+    let command = match cli.command {
+        Some(command) => command,
+        None => return 0,
+    };
+
+    let password = &cli.password;
+    let result = match command {
+        CommandEnum::List { website } => commands::list(password, website.as_deref(), cli.format),
+        CommandEnum::Search { query } => {
+            commands::search(password, &query, cli.format, cli.show_secrets)
+        }
+        CommandEnum::Get {
+            website,
+            email,
+            field,
+        } => commands::get(
+            password,
+            &website,
+            email.as_deref(),
+            field,
+            cli.format,
+            cli.show_secrets,
+        ),
+        CommandEnum::Add {
+            website,
+            email,
+            fields,
+        } => commands::add(password, &website, &email, fields),
+        CommandEnum::Edit {
+            website,
+            email,
+            fields,
+        } => commands::edit(password, &website, email.as_deref(), fields),
+        CommandEnum::Rm { website, email } => {
+            commands::remove(password, &website, email.as_deref())
+        }
+        CommandEnum::Generate {
+            length,
+            passphrase,
+            words,
+            no_symbols,
+            rules,
+        } => commands::generate(length, passphrase, words, no_symbols, rules.as_deref()),
+        CommandEnum::Audit { breach_list } => commands::audit(password, breach_list, cli.format),
+        CommandEnum::BreachIndex {
+            source,
+            destination,
+        } => commands::build_breach_index(&source, &destination),
+        CommandEnum::Lock => commands::lock(),
+        #[cfg(unix)]
+        CommandEnum::Agent { command } => agent_commands::run(command, password, cli.format),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            let err = CliError::from_error(err);
+            match cli.format {
+                FormatEnum::Text => eprintln!("Error: {}", err),
+                FormatEnum::Json => eprintln!("{}", output::format_error(&err)),
+            }
+            err.get_exit_code()
+        }
+    }
+}
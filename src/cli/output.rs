@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[cfg(unix)]
+use crate::agent::protocol::AgentStatus;
+use crate::app::health::HealthReport;
+
+use super::CliError;
+
+/// The version of the JSON output. It changes only when a field is removed or changes meaning;
+/// new fields can appear in any version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Every JSON document has the schema version next to its content.
+#[derive(Serialize)]
+struct Versioned<T: Serialize> {
+    version: u32,
+    #[serde(flatten)]
+    content: T,
+}
+
+/// An entry without its secrets, which are only included when asked for.
+#[derive(Serialize)]
+pub struct EntryOutput {
+    pub website: String,
+    pub email: String,
+    pub username: String,
+    pub tags: Vec<String>,
+    pub has_otp: bool,
+    pub attachment_count: usize,
+    pub created_at: Option<DateTime<Utc>>,
+    pub modified_at: Option<DateTime<Utc>>,
+    pub password_modified_at: Option<DateTime<Utc>>,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp_code: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SearchResultOutput {
+    #[serde(flatten)]
+    pub entry: EntryOutput,
+    // The names of the fields that contain the query.
+    pub matched_fields: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct EntriesOutput<'a> {
+    entries: &'a [EntryOutput],
+}
+
+#[derive(Serialize)]
+struct EntryDocumentOutput<'a> {
+    entry: &'a EntryOutput,
+}
+
+#[derive(Serialize)]
+struct SearchOutput<'a> {
+    query: &'a str,
+    results: &'a [SearchResultOutput],
+}
+
+#[derive(Serialize)]
+struct AuditOutput<'a> {
+    report: &'a HealthReport,
+}
+
+#[cfg(unix)]
+#[derive(Serialize)]
+struct AgentStatusOutput<'a> {
+    agent: &'a AgentStatus,
+}
+
+#[derive(Serialize)]
+struct ErrorOutput<'a> {
+    error: ErrorDetailOutput<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorDetailOutput<'a> {
+    code: &'a str,
+    exit_code: i32,
+    message: String,
+}
+
+fn to_json<T: Serialize>(content: T) -> String {
+    serde_json::to_string_pretty(&Versioned {
+        version: SCHEMA_VERSION,
+        content,
+    })
+    .expect("the output types always serialize")
+}
+
+pub fn format_entries(entries: &[EntryOutput]) -> String {
+    to_json(EntriesOutput { entries })
+}
+
+pub fn format_entry(entry: &EntryOutput) -> String {
+    to_json(EntryDocumentOutput { entry })
+}
+
+pub fn format_search_results(query: &str, results: &[SearchResultOutput]) -> String {
+    to_json(SearchOutput { query, results })
+}
+
+pub fn format_audit(report: &HealthReport) -> String {
+    to_json(AuditOutput { report })
+}
+
+#[cfg(unix)]
+pub fn format_agent_status(status: &AgentStatus) -> String {
+    to_json(AgentStatusOutput { agent: status })
+}
+
+pub fn format_error(err: &CliError) -> String {
+    to_json(ErrorOutput {
+        error: ErrorDetailOutput {
+            code: err.get_code(),
+            exit_code: err.get_exit_code(),
+            message: err.to_string(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versioned_output() {
+        let error: serde_json::Value =
+            serde_json::from_str(&format_error(&CliError::WrongPassword)).unwrap();
+        assert_eq!(error["version"], SCHEMA_VERSION);
+        assert_eq!(error["error"]["code"], "wrong_password");
+        assert_eq!(error["error"]["exit_code"], 3);
+
+        let entry = EntryOutput {
+            website: "example.com".to_string(),
+            email: "me@example.com".to_string(),
+            username: String::new(),
+            tags: Vec::new(),
+            has_otp: false,
+            attachment_count: 0,
+            created_at: None,
+            modified_at: None,
+            password_modified_at: None,
+            last_accessed_at: None,
+            password: None,
+            notes: None,
+            otp_code: None,
+        };
+        let entries: serde_json::Value = serde_json::from_str(&format_entries(&[entry])).unwrap();
+        assert_eq!(entries["entries"][0]["website"], "example.com");
+        assert!(entries["entries"][0].get("password").is_none());
+    }
+}
@@ -0,0 +1,156 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use super::CliError;
+
+pub const PASSWORD_ENV_VAR: &str = "KEYLOX_PASSWORD";
+
+/// Where the master password comes from when no one is there to type it. At most one can be
+/// given; without any, piped stdin is read, and a terminal is prompted without echo.
+#[derive(clap::Args)]
+pub struct PasswordArgs {
+    /// Reads the master password from the first line of an open file descriptor
+    #[arg(
+        long,
+        global = true,
+        value_name = "FD",
+        conflicts_with_all = ["password_file", "password_env"]
+    )]
+    pub password_fd: Option<u32>,
+    /// Reads the master password from the first line of a file, which only you should be able
+    /// to read
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "password_env"
+    )]
+    pub password_file: Option<PathBuf>,
+    /// Reads the master password from KEYLOX_PASSWORD. Other processes of your user and child
+    /// processes can see it
+    #[arg(long, global = true)]
+    pub password_env: bool,
+}
+
+/// Gets the master password from, in order: --password-fd, --password-file, KEYLOX_PASSWORD with
+/// --password-env, stdin when it is not a terminal, and finally a no-echo terminal prompt.
+pub fn read_master_password(args: &PasswordArgs) -> Result<String, CliError> {
+    if let Some(fd) = args.password_fd {
+        return read_fd(fd);
+    }
+    if let Some(path) = &args.password_file {
+        return read_file(path);
+    }
+    if args.password_env {
+        return read_env();
+    }
+    if env::var_os(PASSWORD_ENV_VAR).is_some() {
+        eprintln!(
+            "Warning: {} is ignored unless --password-env is given",
+            PASSWORD_ENV_VAR
+        );
+    }
+
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return read_first_line(stdin.lock(), "stdin");
+    }
+    rpassword::prompt_password("Master password: ").map_err(|err| {
+        CliError::Locked(format!(
+            "The master password is needed but could not be asked for: {}",
+            err
+        ))
+    })
+}
+
+#[cfg(unix)]
+fn read_fd(fd: u32) -> Result<String, CliError> {
+    // Opening the descriptor through /dev/fd leaves the original open and needs no unsafe code.
+    let source = format!("file descriptor {}", fd);
+    let file = File::open(format!("/dev/fd/{}", fd))
+        .map_err(|err| CliError::Locked(format!("Cannot read {}: {}", source, err)))?;
+    read_first_line(BufReader::new(file), &source)
+}
+
+#[cfg(not(unix))]
+fn read_fd(_fd: u32) -> Result<String, CliError> {
+    Err(CliError::Failed(
+        "--password-fd is only supported on Unix, use --password-file or stdin".to_string(),
+    ))
+}
+
+fn read_file(path: &Path) -> Result<String, CliError> {
+    let source = path.display().to_string();
+    let file = File::open(path)
+        .map_err(|err| CliError::Locked(format!("Cannot read {}: {}", source, err)))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let is_shared = file
+            .metadata()
+            .is_ok_and(|metadata| metadata.permissions().mode() & 0o077 != 0);
+        if is_shared {
+            eprintln!(
+                "Warning: other users can access {}, restrict it with chmod 600",
+                source
+            );
+        }
+    }
+
+    read_first_line(BufReader::new(file), &source)
+}
+
+fn read_env() -> Result<String, CliError> {
+    let password = env::var(PASSWORD_ENV_VAR).map_err(|_| {
+        CliError::Locked(format!(
+            "--password-env is given but {} is not set",
+            PASSWORD_ENV_VAR
+        ))
+    })?;
+    eprintln!(
+        "Warning: {} can be read by other processes of your user and is inherited by child \
+         processes, prefer --password-fd or stdin",
+        PASSWORD_ENV_VAR
+    );
+    Ok(password)
+}
+
+/// Reads the first line, without its line ending, so that `echo` and password files with a
+/// trailing newline work.
+fn read_first_line(mut reader: impl BufRead, source: &str) -> Result<String, CliError> {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|err| CliError::Locked(format!("Cannot read {}: {}", source, err)))?;
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    if line.is_empty() {
+        return Err(CliError::Locked(format!(
+            "No master password on {}",
+            source
+        )));
+    }
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_first_line() {
+        let read = |input: &str| read_first_line(input.as_bytes(), "test");
+        assert_eq!(read("secret\n").unwrap(), "secret");
+        assert_eq!(read("secret\r\nsecond line\n").unwrap(), "secret");
+        assert_eq!(read(" spaces kept ").unwrap(), " spaces kept ");
+        assert!(matches!(read(""), Err(CliError::Locked(_))));
+        assert!(matches!(read("\n"), Err(CliError::Locked(_))));
+    }
+}
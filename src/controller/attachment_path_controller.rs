@@ -0,0 +1,36 @@
+use crate::app::app::{App, AttachmentActionEnum};
+
+use super::text_input_controller;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+pub fn handle_attachment_path(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    match key_event.code {
+        KeyCode::Enter => {
+            let result = match app.attachment_action {
+                Some(AttachmentActionEnum::Add) => app.add_attachment_from_path(),
+                Some(AttachmentActionEnum::Export) => app.export_selected_attachment(),
+                None => Ok(String::new()),
+            };
+
+            match result {
+                Ok(message) => {
+                    app.close_attachment_prompt();
+                    app.status_message = Some(message);
+                }
+                Err(err) => {
+                    app.status_message = Some(err.to_string());
+                }
+            }
+        }
+        KeyCode::Esc => {
+            app.close_attachment_prompt();
+        }
+        _ => {
+            text_input_controller::handle_text_input(&mut app.attachment_path_input, key_event);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,66 @@
+use crate::app::{
+    app::{App, CurrentScreen},
+    confirmation::ConfirmationActionEnum,
+};
+
+use super::specific_credential_controller;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+pub fn handle_confirmation(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    let confirmation = app.confirmation.as_mut()?;
+
+    let action = match key_event.code {
+        KeyCode::Esc => ConfirmationActionEnum::Cancel,
+        KeyCode::Enter => confirmation.get_selected_action(),
+        KeyCode::Left | KeyCode::Up | KeyCode::BackTab => {
+            confirmation.select_previous_choice();
+            return None;
+        }
+        KeyCode::Right | KeyCode::Down | KeyCode::Tab => {
+            confirmation.select_next_choice();
+            return None;
+        }
+        KeyCode::Char(value) => confirmation.get_action_for_key(value)?,
+        _ => return None,
+    };
+
+    app.confirmation = None;
+    perform_action(app, action)
+}
+
+fn perform_action(app: &mut App, action: ConfirmationActionEnum) -> Option<io::Result<bool>> {
+    match action {
+        ConfirmationActionEnum::Cancel => {}
+        ConfirmationActionEnum::MoveToTrash => {
+            app.remove_selected_credential();
+        }
+        ConfirmationActionEnum::PurgeFromTrash => {
+            app.purge_selected_trash_credential();
+        }
+        ConfirmationActionEnum::RemoveAttachment => {
+            app.remove_selected_attachment();
+        }
+        ConfirmationActionEnum::DiscardChanges => {
+            app.current_screen = CurrentScreen::WebsiteCredentialScreen;
+            app.discard_unsaved_credentials();
+        }
+        ConfirmationActionEnum::OverwriteCredential => {
+            specific_credential_controller::save_credential(app);
+        }
+        ConfirmationActionEnum::MergeOtpImport => {
+            let merged = app.merge_otp_import();
+            app.status_message = Some(format!("Imported {} one-time password(s)", merged));
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+        }
+        ConfirmationActionEnum::SaveAndQuit => {
+            return Some(Ok(true));
+        }
+        ConfirmationActionEnum::QuitWithoutSaving => {
+            return Some(Ok(false));
+        }
+    }
+
+    None
+}
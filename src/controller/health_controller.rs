@@ -0,0 +1,36 @@
+use crate::app::app::{App, CurrentScreen};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+pub fn handle_health(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    let finding_count = app
+        .health_report
+        .as_ref()
+        .map_or(0, |report| report.findings.len());
+
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+        }
+        KeyCode::Enter => {
+            app.open_selected_health_finding();
+        }
+        KeyCode::Char('+') => {
+            app.change_password_max_age(30);
+        }
+        KeyCode::Char('-') => {
+            app.change_password_max_age(-30);
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            app.selected_health_index = app.selected_health_index.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Tab => {
+            app.selected_health_index =
+                (app.selected_health_index + 1).min(finding_count.saturating_sub(1));
+        }
+        _ => {}
+    }
+
+    None
+}
@@ -0,0 +1,28 @@
+use crate::app::app::App;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Handles (^Z) and (^Y) on the screens listing credentials. Returns true if the key was used.
+pub fn handle_history(app: &mut App, key_event: KeyEvent) -> bool {
+    match key_event {
+        KeyEvent {
+            code: KeyCode::Char('z'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.undo();
+            true
+        }
+        KeyEvent {
+            code: KeyCode::Char('y'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.redo();
+            true
+        }
+        _ => false,
+    }
+}
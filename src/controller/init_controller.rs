@@ -8,5 +8,18 @@ pub fn handle_init(app: &mut App) {
         app.current_screen = CurrentScreen::NewPasswordRequiredScreen;
     } else {
         app.current_screen = CurrentScreen::MasterPasswordRequiredScreen;
+
+        if app.load_credentials_from_keyring() {
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+            app.status_message =
+                Some("Unlocked with the key cached in the kernel keyring".to_string());
+            return;
+        }
+
+        #[cfg(unix)]
+        if crate::agent::client::unlock_app(app) {
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+            app.status_message = Some("Unlocked with the key of the agent".to_string());
+        }
     }
 }
@@ -1,9 +1,15 @@
 use crate::app::app::{App, CurrentScreen, CurrentlyEditingCredentialField};
 
+use super::history_controller;
+
 use crossterm::event::{KeyCode, KeyEvent};
 use std::io;
 
 pub fn handle_main_credentials(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    if history_controller::handle_history(app, key_event) {
+        return None;
+    }
+
     match key_event.code {
         KeyCode::Enter => {
             app.current_screen = CurrentScreen::WebsiteCredentialScreen;
@@ -11,8 +17,13 @@ pub fn handle_main_credentials(app: &mut App, key_event: KeyEvent) -> Option<io:
         }
         KeyCode::Esc | KeyCode::Char('q') => {
             if app.unsaved_changes {
-                app.current_screen = CurrentScreen::Exiting;
+                app.ask_to_exit();
             } else {
+                if app.has_unsaved_access_times() {
+                    if let Err(err) = app.save_changes() {
+                        return Some(Err(io::Error::other(err.to_string())));
+                    }
+                }
                 return Some(Ok(false));
             }
         }
@@ -20,6 +31,27 @@ pub fn handle_main_credentials(app: &mut App, key_event: KeyEvent) -> Option<io:
             app.current_screen = CurrentScreen::SpecificCredentialScreen;
             app.currently_editing_credential_field = Some(CurrentlyEditingCredentialField::Website);
         }
+        KeyCode::Char('i') => {
+            app.current_screen = CurrentScreen::OtpImportScreen;
+        }
+        KeyCode::Char('t') => {
+            app.selected_trash_index = 0;
+            app.current_screen = CurrentScreen::TrashScreen;
+        }
+        KeyCode::Char('r') => {
+            app.selected_rotation_index = 0;
+            app.current_screen = CurrentScreen::RotationScreen;
+        }
+        KeyCode::Char('h') => {
+            app.selected_health_index = 0;
+            app.open_health_report();
+        }
+        KeyCode::Char('s') => {
+            app.cycle_sort_order();
+        }
+        KeyCode::Char('/') => {
+            app.open_search();
+        }
         KeyCode::Up | KeyCode::BackTab => {
             if app.selected_website_index > 0 {
                 app.selected_website_index -= 1;
@@ -1,32 +1,35 @@
 use crate::app::app::{App, CurrentScreen};
 
-use crossterm::event::{KeyCode, KeyEvent};
+use super::text_input_controller;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::io;
 
 pub fn handle_master_password(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    if key_event.code == KeyCode::Char('r') && key_event.modifiers == KeyModifiers::CONTROL {
+        app.toggle_secrets_revealed();
+        return None;
+    }
+
     match key_event.code {
         KeyCode::Enter => {
-            let password_input = app.master_password_input.clone();
+            let password_input = app.master_password_input.take_value();
 
             match app.load_credentials(&password_input) {
                 Ok(_) => {
+                    app.cache_master_key();
+                    app.hide_secrets();
                     app.current_screen = CurrentScreen::MainCredentialScreen;
                 }
                 Err(_) => {}
             }
-
-            app.master_password_input.clear();
         }
         KeyCode::Esc => {
             return Some(Ok(false));
         }
-        KeyCode::Backspace => {
-            app.master_password_input.pop();
-        }
-        KeyCode::Char(value) => {
-            app.master_password_input.push(value);
+        _ => {
+            text_input_controller::handle_text_input(&mut app.master_password_input, key_event);
         }
-        _ => {}
     }
 
     return None;
@@ -1,7 +1,17 @@
-pub(super) mod exit_controller;
+pub(super) mod attachment_path_controller;
+pub(super) mod confirmation_controller;
+pub(super) mod health_controller;
+pub(super) mod history_controller;
 pub(super) mod init_controller;
 pub(super) mod main_credentials_controller;
 pub(super) mod master_password_controller;
 pub(super) mod new_password_controller;
+pub(super) mod otp_import_controller;
+pub(super) mod password_generator_controller;
+pub(super) mod rotation_controller;
+pub(super) mod save_controller;
+pub(super) mod search_controller;
 pub(super) mod specific_credential_controller;
+pub(super) mod text_input_controller;
+pub(super) mod trash_controller;
 pub(super) mod website_credentials_controller;
@@ -1,29 +1,31 @@
-use crate::app::app::{App, CurrentScreen};
+use crate::app::app::App;
 
-use crossterm::event::{KeyCode, KeyEvent};
+use super::text_input_controller;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::io;
 
 // TODO: handle state better
 pub fn handle_new_password(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    if key_event.code == KeyCode::Char('g') && key_event.modifiers == KeyModifiers::CONTROL {
+        app.open_master_password_generator();
+        return None;
+    }
+    if key_event.code == KeyCode::Char('r') && key_event.modifiers == KeyModifiers::CONTROL {
+        app.toggle_secrets_revealed();
+        return None;
+    }
+
     match key_event.code {
         KeyCode::Enter => {
-            //app.master_key =
-            app.generate_initial_master_key_from_password(&app.new_password_input.clone());
-            app.new_password_input.clear();
-            app.current_screen = CurrentScreen::MainCredentialScreen;
+            app.set_new_master_password();
         }
         KeyCode::Esc => {
             return Some(Ok(false));
         }
-        KeyCode::Backspace => {
-            // TODO
-            app.new_password_input.pop();
-        }
-        KeyCode::Char(value) => {
-            // TODO
-            app.new_password_input.push(value);
+        _ => {
+            text_input_controller::handle_text_input(&mut app.new_password_input, key_event);
         }
-        _ => {}
     }
 
     return None;
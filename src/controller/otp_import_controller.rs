@@ -0,0 +1,58 @@
+use crate::app::app::{App, CurrentScreen};
+
+use super::text_input_controller;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+pub fn handle_otp_import(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    if app.otp_import_items.is_empty() {
+        handle_otp_import_input(app, key_event);
+    } else {
+        handle_otp_import_preview(app, key_event);
+    }
+
+    None
+}
+
+fn handle_otp_import_input(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Enter => {
+            if let Err(err) = app.load_otp_import() {
+                app.status_message = Some(err.to_string());
+            }
+        }
+        KeyCode::Esc => {
+            app.discard_otp_import();
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+        }
+        _ => {
+            text_input_controller::handle_text_input(&mut app.otp_import_input, key_event);
+        }
+    }
+}
+
+fn handle_otp_import_preview(app: &mut App, key_event: KeyEvent) {
+    match key_event.code {
+        KeyCode::Enter => {
+            app.ask_to_merge_otp_import();
+        }
+        KeyCode::Esc => {
+            app.otp_import_items.clear();
+        }
+        KeyCode::Char(' ') => {
+            app.toggle_selected_otp_import_item();
+        }
+        KeyCode::Char('a') => {
+            app.accept_all_otp_import_items();
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            app.selected_otp_import_index = app.selected_otp_import_index.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Tab => {
+            app.selected_otp_import_index =
+                (app.selected_otp_import_index + 1).min(app.otp_import_items.len() - 1);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,77 @@
+use crate::app::app::App;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::io;
+
+pub fn handle_password_generator(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    let is_editing_text = app.get_selected_generator_option().is_text();
+
+    match key_event {
+        KeyEvent {
+            code: KeyCode::Char('g'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.regenerate_password();
+        }
+        KeyEvent {
+            code: KeyCode::Enter,
+            ..
+        } => {
+            app.accept_generated_password();
+        }
+        KeyEvent {
+            code: KeyCode::Esc, ..
+        } => {
+            app.close_password_generator();
+        }
+        KeyEvent {
+            code: KeyCode::Up | KeyCode::BackTab,
+            ..
+        } => {
+            app.selected_generator_option = app.selected_generator_option.saturating_sub(1);
+        }
+        KeyEvent {
+            code: KeyCode::Down | KeyCode::Tab,
+            ..
+        } => {
+            app.selected_generator_option =
+                (app.selected_generator_option + 1).min(app.get_generator_options().len() - 1);
+        }
+        KeyEvent {
+            code: KeyCode::Left,
+            ..
+        } => {
+            app.adjust_generator_option(-1);
+        }
+        KeyEvent {
+            code: KeyCode::Right,
+            ..
+        } => {
+            app.adjust_generator_option(1);
+        }
+        KeyEvent {
+            code: KeyCode::Backspace,
+            ..
+        } if is_editing_text => {
+            app.pop_generator_text();
+        }
+        KeyEvent {
+            code: KeyCode::Char(value),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            ..
+        } if is_editing_text => {
+            app.push_generator_text(value);
+        }
+        KeyEvent {
+            code: KeyCode::Char(' '),
+            ..
+        } => {
+            app.toggle_generator_option();
+        }
+        _ => {}
+    }
+
+    None
+}
@@ -0,0 +1,57 @@
+use crate::app::app::{App, CurrentScreen};
+
+use super::text_input_controller;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+pub fn handle_rotation(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    if app.rotation_defaults_input.is_some() {
+        handle_rotation_defaults_input(app, key_event);
+        return None;
+    }
+
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+        }
+        KeyCode::Enter => {
+            app.open_selected_due_credential();
+        }
+        KeyCode::Char('d') => {
+            app.edit_rotation_defaults();
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            app.selected_rotation_index = app.selected_rotation_index.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Tab => {
+            let due_count = app.credentials.get_due_for_rotation().len();
+            app.selected_rotation_index =
+                (app.selected_rotation_index + 1).min(due_count.saturating_sub(1));
+        }
+        _ => {}
+    }
+
+    None
+}
+
+fn handle_rotation_defaults_input(app: &mut App, key_event: KeyEvent) {
+    let input = match &mut app.rotation_defaults_input {
+        Some(input) => input,
+        None => return,
+    };
+
+    match key_event.code {
+        KeyCode::Enter => {
+            if let Err(err) = app.apply_rotation_defaults() {
+                app.status_message = Some(err);
+            }
+        }
+        KeyCode::Esc => {
+            app.rotation_defaults_input = None;
+        }
+        _ => {
+            text_input_controller::handle_text_input(input, key_event);
+        }
+    }
+}
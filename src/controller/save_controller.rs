@@ -0,0 +1,32 @@
+use crate::app::app::{App, CurrentScreen};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Handles (^S), which writes the vault without quitting, on the screens of an unlocked
+/// vault. The editor handles it itself, to save the credential first. Returns true if the key
+/// was used.
+pub fn handle_save(app: &mut App, key_event: KeyEvent) -> bool {
+    let is_save_key = matches!(
+        key_event,
+        KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        }
+    );
+    if !is_save_key {
+        return false;
+    }
+
+    match app.current_screen {
+        CurrentScreen::Init
+        | CurrentScreen::NewPasswordRequiredScreen
+        | CurrentScreen::MasterPasswordRequiredScreen
+        | CurrentScreen::SpecificCredentialScreen => false,
+        _ => {
+            app.save_vault();
+            true
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::app::app::{App, CurrentScreen};
+
+use super::text_input_controller;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+pub fn handle_search(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    match key_event.code {
+        KeyCode::Esc => {
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+        }
+        KeyCode::Enter => {
+            app.open_selected_search_result();
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            app.selected_search_index = app.selected_search_index.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Tab => {
+            app.selected_search_index =
+                (app.selected_search_index + 1).min(app.search_results.len().saturating_sub(1));
+        }
+        _ => {
+            let previous_query = app.search_input.get_value().to_string();
+            if text_input_controller::handle_text_input(&mut app.search_input, key_event) {
+                app.update_search_results(&previous_query);
+            }
+        }
+    }
+
+    None
+}
@@ -1,37 +1,51 @@
-use crate::app::app::{App, CurrentScreen, CurrentlyEditingCredentialField};
+use crate::app::app::{
+    App, AttachmentActionEnum, CopyFieldEnum, CurrentScreen, CurrentlyEditingCredentialField,
+};
+
+use super::text_input_controller;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::io;
 
 pub fn handle_specific_credential(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    if let Some(CurrentlyEditingCredentialField::Notes) = app.currently_editing_credential_field {
+        if text_input_controller::handle_text_area(&mut app.notes_input, key_event) {
+            return None;
+        }
+    }
+
     match key_event.code {
         //TODO: Refactor to use key_events
         KeyCode::Up | KeyCode::BackTab => {
             app.reverse_cycle_editing_credential();
         }
         KeyCode::Esc => {
-            app.current_screen = CurrentScreen::WebsiteCredentialScreen;
-            app.discard_unsaved_credentials();
+            if app.is_editing_credential_modified() {
+                app.ask_to_discard_changes();
+            } else {
+                app.current_screen = CurrentScreen::WebsiteCredentialScreen;
+                app.discard_unsaved_credentials();
+            }
         }
         KeyCode::Backspace => {
-            if let Some(editing) = &app.currently_editing_credential_field {
-                match editing {
-                    CurrentlyEditingCredentialField::Website => {
-                        app.website_input.pop();
-                    }
-                    CurrentlyEditingCredentialField::Email => {
-                        app.email_input.pop();
-                    }
-                    CurrentlyEditingCredentialField::Username => {
-                        app.username_input.pop();
-                    }
-                    CurrentlyEditingCredentialField::Password => {
-                        app.password_input.pop();
-                    }
-                    CurrentlyEditingCredentialField::Notes => {
-                        app.notes_input.pop();
-                    }
-                }
+            if let Some(CurrentlyEditingCredentialField::Attachments) =
+                app.currently_editing_credential_field
+            {
+                app.ask_to_remove_selected_attachment();
+            }
+        }
+        KeyCode::Left => {
+            if let Some(CurrentlyEditingCredentialField::Attachments) =
+                app.currently_editing_credential_field
+            {
+                app.select_previous_attachment();
+            }
+        }
+        KeyCode::Right => {
+            if let Some(CurrentlyEditingCredentialField::Attachments) =
+                app.currently_editing_credential_field
+            {
+                app.select_next_attachment();
             }
         }
         _ => {}
@@ -44,8 +58,67 @@ pub fn handle_specific_credential(app: &mut App, key_event: KeyEvent) -> Option<
             kind: _,
             state: _,
         } => {
-            app.save_credential();
-            app.current_screen = CurrentScreen::MainCredentialScreen;
+            if app.is_overwriting_other_credential() {
+                app.ask_to_overwrite_credential();
+            } else {
+                save_credential(app);
+            }
+        }
+        KeyEvent {
+            code: KeyCode::Char('o'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.next_hotp_code();
+        }
+        KeyEvent {
+            code: KeyCode::Char('g'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.open_password_generator();
+        }
+        KeyEvent {
+            code: KeyCode::Char('u'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.copy_credential_field(CopyFieldEnum::Username);
+        }
+        KeyEvent {
+            code: KeyCode::Char('p'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.copy_credential_field(CopyFieldEnum::Password);
+        }
+        KeyEvent {
+            code: KeyCode::Char('t'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.copy_credential_field(CopyFieldEnum::OtpCode);
+        }
+        KeyEvent {
+            code: KeyCode::Char('x'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.is_external_editor_requested = true;
+        }
+        KeyEvent {
+            code: KeyCode::Char('r'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: _,
+            state: _,
+        } => {
+            app.toggle_secrets_revealed();
         }
         KeyEvent {
             code: KeyCode::Enter | KeyCode::Tab | KeyCode::Down,
@@ -60,29 +133,41 @@ pub fn handle_specific_credential(app: &mut App, key_event: KeyEvent) -> Option<
             modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
             kind: _,
             state: _,
-        } => {
-            if let Some(editing) = &app.currently_editing_credential_field {
-                match editing {
-                    CurrentlyEditingCredentialField::Website => {
-                        app.website_input.push(value);
-                    }
-                    CurrentlyEditingCredentialField::Email => {
-                        app.email_input.push(value);
-                    }
-                    CurrentlyEditingCredentialField::Username => {
-                        app.username_input.push(value);
-                    }
-                    CurrentlyEditingCredentialField::Password => {
-                        app.password_input.push(value);
-                    }
-                    CurrentlyEditingCredentialField::Notes => {
-                        app.notes_input.push(value);
-                    }
-                }
+        } if matches!(
+            app.currently_editing_credential_field,
+            Some(CurrentlyEditingCredentialField::Attachments)
+        ) =>
+        {
+            handle_attachments_key(app, value);
+        }
+        _ => {
+            if let Some(input) = app.get_focused_text_input() {
+                text_input_controller::handle_text_input(input, key_event);
             }
         }
-        _ => {}
     }
 
     return None;
 }
+
+fn handle_attachments_key(app: &mut App, value: char) {
+    match value.to_ascii_lowercase() {
+        'a' => app.open_attachment_prompt(AttachmentActionEnum::Add),
+        'e' => app.open_attachment_prompt(AttachmentActionEnum::Export),
+        'd' => app.ask_to_remove_selected_attachment(),
+        _ => {}
+    }
+}
+
+/// Saves the credential and writes the vault.
+pub(super) fn save_credential(app: &mut App) {
+    match app.save_credential() {
+        Ok(_) => {
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+            app.save_vault();
+        }
+        Err(err) => {
+            app.status_message = Some(err.to_string());
+        }
+    }
+}
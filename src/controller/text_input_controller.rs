@@ -0,0 +1,50 @@
+use crate::app::{text_area::TextArea, text_input::TextInput};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Edits the input with the key. Returns false for keys it leaves to the screen, such as
+/// Enter, Esc, Tab and the shortcuts of the screen.
+pub fn handle_text_input(input: &mut TextInput, key_event: KeyEvent) -> bool {
+    let is_control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    let is_alt = key_event.modifiers.contains(KeyModifiers::ALT);
+
+    match key_event.code {
+        // AltGr arrives as Ctrl+Alt on some platforms, and types characters as well.
+        KeyCode::Char(value) if is_control == is_alt => input.insert_char(value),
+        KeyCode::Char('w') if is_control => input.delete_word_before(),
+        KeyCode::Char('k') if is_control => input.delete_to_end(),
+        KeyCode::Char('a') if is_control => input.move_home(),
+        KeyCode::Char('e') if is_control => input.move_end(),
+        KeyCode::Backspace if is_control || is_alt => input.delete_word_before(),
+        KeyCode::Backspace => input.delete_before(),
+        KeyCode::Delete => input.delete_after(),
+        KeyCode::Left if is_control || is_alt => input.move_word_left(),
+        KeyCode::Right if is_control || is_alt => input.move_word_right(),
+        KeyCode::Left => input.move_left(),
+        KeyCode::Right => input.move_right(),
+        KeyCode::Home => input.move_home(),
+        KeyCode::End => input.move_end(),
+        _ => return false,
+    }
+    true
+}
+
+/// Edits the text area with the key. Enter starts a new line, and Up and Down move between
+/// lines. Returns false for keys it leaves to the screen, including Up on the first line and
+/// Down on the last one.
+pub fn handle_text_area(area: &mut TextArea, key_event: KeyEvent) -> bool {
+    let is_control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+
+    match key_event.code {
+        KeyCode::Enter if key_event.modifiers == KeyModifiers::NONE => area.insert_newline(),
+        KeyCode::Up => return area.move_up(),
+        KeyCode::Down => return area.move_down(),
+        KeyCode::Home => area.move_line_start(),
+        KeyCode::End => area.move_line_end(),
+        KeyCode::Char('a') if is_control => area.move_line_start(),
+        KeyCode::Char('e') if is_control => area.move_line_end(),
+        KeyCode::Char('k') if is_control => area.delete_to_line_end(),
+        _ => return handle_text_input(area.get_line_editor(), key_event),
+    }
+    true
+}
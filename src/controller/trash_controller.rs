@@ -0,0 +1,40 @@
+use crate::app::app::{App, CurrentScreen};
+
+use super::history_controller;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+pub fn handle_trash(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    if history_controller::handle_history(app, key_event) {
+        return None;
+    }
+
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.current_screen = CurrentScreen::MainCredentialScreen;
+        }
+        KeyCode::Char('r') => {
+            app.restore_selected_trash_credential();
+        }
+        KeyCode::Backspace | KeyCode::Delete => {
+            app.ask_to_purge_selected_trash_credential();
+        }
+        KeyCode::Char('+') => {
+            app.change_trash_retention(1);
+        }
+        KeyCode::Char('-') => {
+            app.change_trash_retention(-1);
+        }
+        KeyCode::Up | KeyCode::BackTab => {
+            app.selected_trash_index = app.selected_trash_index.saturating_sub(1);
+        }
+        KeyCode::Down | KeyCode::Tab => {
+            app.selected_trash_index =
+                (app.selected_trash_index + 1).min(app.credentials.trash.len().saturating_sub(1));
+        }
+        _ => {}
+    }
+
+    None
+}
@@ -1,9 +1,15 @@
-use crate::app::app::{App, CurrentScreen, CurrentlyEditingCredentialField};
+use crate::app::app::{App, CopyFieldEnum, CurrentScreen, CurrentlyEditingCredentialField};
+
+use super::history_controller;
 
 use crossterm::event::{KeyCode, KeyEvent};
 use std::io;
 
 pub fn handle_website_credentials(app: &mut App, key_event: KeyEvent) -> Option<io::Result<bool>> {
+    if history_controller::handle_history(app, key_event) {
+        return None;
+    }
+
     match key_event.code {
         KeyCode::Enter => {
             app.current_screen = CurrentScreen::SpecificCredentialScreen;
@@ -19,9 +25,9 @@ pub fn handle_website_credentials(app: &mut App, key_event: KeyEvent) -> Option<
             app.current_screen = CurrentScreen::SpecificCredentialScreen;
             app.currently_editing_credential_field = Some(CurrentlyEditingCredentialField::Email);
 
-            if app.credentials.get_websites().len() > app.selected_website_index {
-                app.website_input =
-                    app.credentials.get_websites()[app.selected_website_index].clone();
+            if app.websites.len() > app.selected_website_index {
+                app.website_input
+                    .set_value(&app.websites[app.selected_website_index]);
             }
         }
         KeyCode::Up | KeyCode::BackTab => {
@@ -38,8 +44,11 @@ pub fn handle_website_credentials(app: &mut App, key_event: KeyEvent) -> Option<
             }
         }
         KeyCode::Backspace => {
-            app.remove_selected_credential();
+            app.ask_to_move_selected_credential_to_trash();
         }
+        KeyCode::Char('u') => app.copy_credential_field(CopyFieldEnum::Username),
+        KeyCode::Char('p') => app.copy_credential_field(CopyFieldEnum::Password),
+        KeyCode::Char('o') => app.copy_credential_field(CopyFieldEnum::OtpCode),
         _ => {}
     }
 
@@ -1,8 +1,16 @@
+use clap::Parser;
 use crossterm::event::{self, Event};
 use ratatui::{backend::Backend, Terminal};
-use std::{error::Error, io};
+use std::{
+    error::Error,
+    io,
+    time::{Duration, Instant},
+};
 
+#[cfg(unix)]
+mod agent;
 mod app;
+mod cli;
 mod controller;
 mod errors;
 //mod models;
@@ -13,7 +21,16 @@ use crate::{
     ui::ui,
 };
 
+// How often the screen is redrawn when no key is pressed, e.g. to keep OTP codes current.
+const TICK_RATE: Duration = Duration::from_millis(250);
+
 fn main() -> Result<(), Box<dyn Error>> {
+    // run a command without the terminal interface
+    let cli = cli::Cli::parse();
+    if cli.command.is_some() {
+        std::process::exit(cli::run(cli));
+    }
+
     // setup panic hook
     errors::install_hooks()?;
 
@@ -23,6 +40,8 @@ fn main() -> Result<(), Box<dyn Error>> {
     // create app and run it
     let mut app = App::new();
     let res = run_app(&mut terminal, &mut app);
+    // a copied password does not outlive the session
+    let _ = app.clipboard.clear();
 
     // restore terminal
     tui::restore()?;
@@ -30,6 +49,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     if let Ok(do_print) = res {
         if do_print {
             app.save_changes()?;
+            println!("Changes saved");
         }
     } else if let Err(err) = res {
         println!("{err:?}");
@@ -44,64 +64,138 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<
     loop {
         terminal.draw(|f| ui(f, app))?;
 
-        if let Event::Key(key_event) = event::read()? {
-            if key_event.kind == event::KeyEventKind::Release {
-                // Skip events that are not KeyEventKind::Press
+        if let Err(err) = app.clipboard.clear_if_expired(Instant::now()) {
+            app.status_message = Some(err.to_string());
+        }
+
+        if !event::poll(TICK_RATE)? {
+            continue;
+        }
+
+        let key_event = match event::read()? {
+            Event::Key(key_event) => key_event,
+            Event::Paste(text) => {
+                app.paste_text(&text);
                 continue;
             }
-            match app.current_screen {
-                CurrentScreen::Init => {
-                    // todo remove this?
+            _ => continue,
+        };
+
+        if key_event.kind == event::KeyEventKind::Release {
+            // Skip events that are not KeyEventKind::Press
+            continue;
+        }
+        app.status_message = None;
+        // A question over the screen takes the keys until it is answered.
+        if app.confirmation.is_some() {
+            if let Some(res) =
+                controller::confirmation_controller::handle_confirmation(app, key_event)
+            {
+                return res;
+            }
+            app.refresh_unsaved_changes();
+            continue;
+        }
+        if controller::save_controller::handle_save(app, key_event) {
+            continue;
+        }
+        match app.current_screen {
+            CurrentScreen::Init => {
+                // todo remove this?
+            }
+            CurrentScreen::NewPasswordRequiredScreen => {
+                if let Some(res) =
+                    controller::new_password_controller::handle_new_password(app, key_event)
+                {
+                    return res;
                 }
-                CurrentScreen::NewPasswordRequiredScreen => {
-                    if let Some(res) =
-                        controller::new_password_controller::handle_new_password(app, key_event)
-                    {
-                        return res;
-                    }
+            }
+            CurrentScreen::MasterPasswordRequiredScreen => {
+                if let Some(res) =
+                    controller::master_password_controller::handle_master_password(app, key_event)
+                {
+                    return res;
                 }
-                CurrentScreen::MasterPasswordRequiredScreen => {
-                    if let Some(res) =
-                        controller::master_password_controller::handle_master_password(
-                            app, key_event,
-                        )
-                    {
-                        return res;
-                    }
+            }
+            CurrentScreen::MainCredentialScreen => {
+                if let Some(res) =
+                    controller::main_credentials_controller::handle_main_credentials(app, key_event)
+                {
+                    return res;
                 }
-                CurrentScreen::MainCredentialScreen => {
-                    if let Some(res) =
-                        controller::main_credentials_controller::handle_main_credentials(
-                            app, key_event,
-                        )
-                    {
-                        return res;
-                    }
+            }
+            CurrentScreen::WebsiteCredentialScreen => {
+                if let Some(res) =
+                    controller::website_credentials_controller::handle_website_credentials(
+                        app, key_event,
+                    )
+                {
+                    return res;
                 }
-                CurrentScreen::WebsiteCredentialScreen => {
-                    if let Some(res) =
-                        controller::website_credentials_controller::handle_website_credentials(
-                            app, key_event,
-                        )
-                    {
-                        return res;
-                    }
+            }
+            CurrentScreen::SpecificCredentialScreen => {
+                if let Some(res) =
+                    controller::specific_credential_controller::handle_specific_credential(
+                        app, key_event,
+                    )
+                {
+                    return res;
+                }
+            }
+            CurrentScreen::OtpImportScreen => {
+                if let Some(res) =
+                    controller::otp_import_controller::handle_otp_import(app, key_event)
+                {
+                    return res;
+                }
+            }
+            CurrentScreen::AttachmentPathScreen => {
+                if let Some(res) =
+                    controller::attachment_path_controller::handle_attachment_path(app, key_event)
+                {
+                    return res;
+                }
+            }
+            CurrentScreen::PasswordGeneratorScreen => {
+                if let Some(res) =
+                    controller::password_generator_controller::handle_password_generator(
+                        app, key_event,
+                    )
+                {
+                    return res;
+                }
+            }
+            CurrentScreen::TrashScreen => {
+                if let Some(res) = controller::trash_controller::handle_trash(app, key_event) {
+                    return res;
+                }
+            }
+            CurrentScreen::RotationScreen => {
+                if let Some(res) = controller::rotation_controller::handle_rotation(app, key_event)
+                {
+                    return res;
                 }
-                CurrentScreen::SpecificCredentialScreen => {
-                    if let Some(res) =
-                        controller::specific_credential_controller::handle_specific_credential(
-                            app, key_event,
-                        )
-                    {
-                        return res;
-                    }
+            }
+            CurrentScreen::HealthScreen => {
+                if let Some(res) = controller::health_controller::handle_health(app, key_event) {
+                    return res;
                 }
-                CurrentScreen::Exiting => {
-                    if let Some(res) = controller::exit_controller::handle_exit(app, key_event) {
-                        return res;
-                    }
+            }
+            CurrentScreen::SearchScreen => {
+                if let Some(res) = controller::search_controller::handle_search(app, key_event) {
+                    return res;
                 }
             }
         }
+
+        app.refresh_unsaved_changes();
+
+        // The editor takes over the terminal until it exits.
+        if app.is_external_editor_requested {
+            tui::restore()?;
+            app.edit_notes_in_external_editor();
+            tui::resume()?;
+            terminal.clear()?;
+        }
     }
 }
@@ -1,4 +1,8 @@
-use crossterm::{execute, terminal::*};
+use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
+    execute,
+    terminal::*,
+};
 use ratatui::prelude::*;
 use std::io::{self, stdout, Stdout};
 
@@ -7,14 +11,20 @@ pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
 /// Initialize the terminal
 pub fn init() -> io::Result<Tui> {
-    execute!(stdout(), EnterAlternateScreen)?;
-    enable_raw_mode()?;
+    resume()?;
     Terminal::new(CrosstermBackend::new(stdout()))
 }
 
+/// Takes the terminal back after `restore`, e.g. once an external editor exits
+pub fn resume() -> io::Result<()> {
+    // A pasted text arrives as a single event instead of one key event per character.
+    execute!(stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+    enable_raw_mode()
+}
+
 /// Restore the terminal to its original state
 pub fn restore() -> io::Result<()> {
-    execute!(stdout(), LeaveAlternateScreen)?;
+    execute!(stdout(), DisableBracketedPaste, LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
 }
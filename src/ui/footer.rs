@@ -1,4 +1,4 @@
-use crate::app::app::{App, CurrentScreen};
+use crate::app::app::{App, CurrentScreen, CurrentlyEditingCredentialField};
 
 use ratatui::{
     layout::Rect,
@@ -9,37 +9,130 @@ use ratatui::{
 };
 
 pub fn render_footer(f: &mut Frame, app: &App, area: Rect) {
-    let current_keys_hint = {
+    if let Some(message) = &app.status_message {
+        let status_footer = Paragraph::new(Line::from(Span::styled(
+            message.clone(),
+            Style::default().fg(Color::Yellow),
+        )))
+        .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(status_footer, area);
+        return;
+    }
+
+    let current_keys_hint = if app.confirmation.is_some() {
+        Span::styled(
+            "(Esc) to cancel / (Left | Right) to choose / (Return) to confirm the choice",
+            Style::default().fg(Color::Red),
+        )
+    } else {
         match app.current_screen {
             CurrentScreen::Init => Span::styled(
                 "", Style::default()
             ),
             CurrentScreen::NewPasswordRequiredScreen => Span::styled(
-                "Enter New Password", Style::default().fg(Color::Red)
+                "Enter New Password / (^G) to generate a passphrase / (^R) to reveal", Style::default().fg(Color::Red)
             ),
-            CurrentScreen::MasterPasswordRequiredScreen => Span::styled("Enter Master Password", Style::default().fg(Color::Red)
+            CurrentScreen::MasterPasswordRequiredScreen => Span::styled("Enter Master Password / (^R) to reveal", Style::default().fg(Color::Red)
             ),
             CurrentScreen::MainCredentialScreen => Span::styled(
-                "(Esc) to quit / (N) to make a new credential / (Return) to view",
+                "(Esc) to quit / (^S) to save / (N) to make a new credential / (Return) to view / (S) to sort / (/) to search / (I) to import OTP / (T) to open the trash / (R) for rotation / (H) for the health report",
                 Style::default().fg(Color::Red),
             ),
             CurrentScreen::WebsiteCredentialScreen => Span::styled(
-                "(Esc) to quit / (N) to make a new credential / (Return) to view / (Backspace) to delete",
+                "(Esc) to quit / (N) to make a new credential / (Return) to view / (Backspace) to move to the trash / (U | P | O) to copy the username, password or OTP code",
                 Style::default().fg(Color::Red),
             ),
+            CurrentScreen::SpecificCredentialScreen
+                if matches!(
+                    app.currently_editing_credential_field,
+                    Some(CurrentlyEditingCredentialField::Attachments)
+                ) =>
+            {
+                Span::styled(
+                    "(Esc) to quit / (^S) to save / (A) to attach a file / (E) to export / (D) to remove / (Left | Right) to select",
+                    Style::default().fg(Color::Red),
+                )
+            }
+            CurrentScreen::SpecificCredentialScreen
+                if matches!(
+                    app.currently_editing_credential_field,
+                    Some(CurrentlyEditingCredentialField::Notes)
+                ) =>
+            {
+                Span::styled(
+                    "(Esc) to quit / (^S) to save / (Return) for a new line / (Tab) for the next field / (^X) to edit in $EDITOR",
+                    Style::default().fg(Color::Red),
+                )
+            }
             CurrentScreen::SpecificCredentialScreen => Span::styled(
-                "(Esc) to quit / (Return | ^S) to save",
+                "(Esc) to quit / (Return | ^S) to save / (^O) next HOTP code / (^G) to generate a password / (^U | ^P | ^T) to copy the username, password or OTP code / (^R) to reveal / (^X) to edit the notes in $EDITOR",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::OtpImportScreen if app.otp_import_items.is_empty() => Span::styled(
+                "(Esc) to cancel / (Return) to preview the accounts",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::OtpImportScreen => Span::styled(
+                "(Esc) to go back / (Space) to accept / (A) to accept all / (Return) to merge the accepted accounts",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::AttachmentPathScreen => Span::styled(
+                "(Esc) to cancel / (Return) to confirm",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::PasswordGeneratorScreen => Span::styled(
+                "(Esc) to cancel / (Return) to use the password / (^G) to regenerate / (Left | Right | Space) to change the option",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::TrashScreen => Span::styled(
+                "(Esc) to go back / (R) to restore / (Backspace) to delete permanently / (+ | -) to change the retention",
                 Style::default().fg(Color::Red),
             ),
-            CurrentScreen::Exiting => Span::styled(
-                "(Esc | Return | Q) to quit without saving / (W | S) to save and quit",
+            CurrentScreen::RotationScreen if app.rotation_defaults_input.is_some() => Span::styled(
+                "(Esc) to cancel / (Return) to save the defaults",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::RotationScreen => Span::styled(
+                "(Esc) to go back / (Return) to edit the credential / (D) to edit the defaults by tag",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::HealthScreen => Span::styled(
+                "(Esc) to go back / (Return) to edit the credential / (+ | -) to change the password age limit",
+                Style::default().fg(Color::Red),
+            ),
+            CurrentScreen::SearchScreen => Span::styled(
+                "(Esc) to go back / (Return) to edit the credential / (Up | Down) to select",
                 Style::default().fg(Color::Red),
             ),
         }
     };
 
+    let mut spans = vec![current_keys_hint];
+    if app.confirmation.is_none()
+        && matches!(
+            app.current_screen,
+            CurrentScreen::MainCredentialScreen
+                | CurrentScreen::WebsiteCredentialScreen
+                | CurrentScreen::TrashScreen
+        )
+    {
+        if let Some(description) = app.history.get_undo_description() {
+            spans.push(Span::styled(
+                format!(" / (^Z) to undo {}", description),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        if app.history.get_redo_description().is_some() {
+            spans.push(Span::styled(
+                " / (^Y) to redo",
+                Style::default().fg(Color::Red),
+            ));
+        }
+    }
+
     let key_notes_footer =
-        Paragraph::new(Line::from(current_keys_hint)).block(Block::default().borders(Borders::ALL));
+        Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL));
 
     f.render_widget(key_notes_footer, area);
 }
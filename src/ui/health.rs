@@ -0,0 +1,95 @@
+use crate::app::app::App;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use super::helpers;
+
+pub fn render_health_screen(f: &mut Frame, app: &App, area: Rect) {
+    let report = match &app.health_report {
+        Some(report) => report,
+        None => return,
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
+
+    let summary = if report.findings.is_empty() {
+        "No problems found".to_string()
+    } else {
+        report
+            .get_counts()
+            .iter()
+            .map(|(issue, count)| format!("{}: {}", issue.get_name(), count))
+            .collect::<Vec<String>>()
+            .join(" / ")
+    };
+    let max_age = if report.max_password_age_days == 0 {
+        "Summary (password age not checked)".to_string()
+    } else {
+        format!(
+            "Summary (passwords are old after {} days)",
+            report.max_password_age_days
+        )
+    };
+    let summary_text =
+        Paragraph::new(summary).block(Block::default().title(max_age).borders(Borders::ALL));
+    f.render_widget(summary_text, chunks[0]);
+
+    // Each group of findings starts with a heading row, which cannot be selected.
+    let mut rows: Vec<Row> = Vec::new();
+    let mut selected_row = 0;
+    let mut previous_issue = None;
+    for (i, finding) in report.findings.iter().enumerate() {
+        if previous_issue != Some(finding.issue) {
+            let count = report
+                .findings
+                .iter()
+                .filter(|f| f.issue == finding.issue)
+                .count();
+            rows.push(Row::new(vec![Cell::from(Span::styled(
+                format!("{} ({})", finding.issue.get_name(), count),
+                Style::default().add_modifier(Modifier::BOLD),
+            ))]));
+            previous_issue = Some(finding.issue);
+        }
+
+        let style = if i == app.selected_health_index {
+            selected_row = rows.len();
+            Style::default().bg(Color::LightYellow).fg(Color::Black)
+        } else {
+            Style::default()
+        };
+        rows.push(Row::new(vec![
+            Cell::from(Span::styled(
+                format!("{: <1000}", format!("  {}", finding.website)),
+                style,
+            )),
+            Cell::from(Span::styled(format!("{: <1000}", finding.email), style)),
+            Cell::from(Span::styled(format!("{: <1000}", finding.detail), style)),
+        ]));
+    }
+
+    let height = chunks[1].height - 2;
+    let visible_rows = helpers::get_visible_rows(selected_row, rows, height);
+
+    let table = Table::new(
+        visible_rows,
+        [
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Percentage(50),
+        ],
+    )
+    .column_spacing(0)
+    .block(Block::default().title("Vault health").borders(Borders::ALL));
+
+    f.render_widget(table, chunks[1]);
+}
@@ -1,5 +1,10 @@
+use chrono::{DateTime, Local, Utc};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
 use ratatui::widgets::Row;
 
+use crate::app::password_strength::{PasswordStrength, MAX_SCORE};
+
 pub fn get_visible_rows(selected_index: usize, rows: Vec<Row>, height: u16) -> Vec<Row> {
     if rows.len() <= 0 {
         return Vec::new();
@@ -9,3 +14,74 @@ pub fn get_visible_rows(selected_index: usize, rows: Vec<Row>, height: u16) -> V
     let last_item_in_row = std::cmp::min(first_item_in_row + height as usize, rows.len() - 1);
     rows[first_item_in_row..=last_item_in_row].to_vec()
 }
+
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / 1024.0 / 1024.0)
+    }
+}
+
+/// Describes how far a rotation due date is, for example "overdue by 3 days".
+pub fn format_due_date(due_date: DateTime<Utc>) -> String {
+    let days = (due_date - Utc::now()).num_days();
+    if days < 0 {
+        format!("overdue by {} day(s)", -days)
+    } else if days == 0 {
+        "due today".to_string()
+    } else {
+        format!("due in {} day(s)", days)
+    }
+}
+
+pub fn get_due_date_color(due_date: DateTime<Utc>) -> Color {
+    if due_date <= Utc::now() {
+        Color::Red
+    } else {
+        Color::Magenta
+    }
+}
+
+pub fn get_strength_color(score: u8) -> Color {
+    match score {
+        0 => Color::Red,
+        1 => Color::LightRed,
+        2 => Color::Yellow,
+        3 => Color::Green,
+        _ => Color::LightGreen,
+    }
+}
+
+/// A strength meter such as "███░ Strong, 3 years to crack".
+pub fn format_strength_meter(strength: &PasswordStrength) -> Line<'static> {
+    let filled = (strength.get_score() + 1) as usize;
+    let color = get_strength_color(strength.get_score());
+    Line::from(vec![
+        Span::styled("█".repeat(filled), Style::default().fg(color)),
+        Span::styled(
+            "░".repeat(MAX_SCORE as usize + 1 - filled),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            format!(
+                " {}, {} to crack",
+                strength.get_label(),
+                strength.get_crack_time()
+            ),
+            Style::default().fg(color),
+        ),
+    ])
+}
+
+pub fn format_timestamp(timestamp: Option<DateTime<Utc>>) -> String {
+    match timestamp {
+        Some(timestamp) => timestamp
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        None => "never".to_string(),
+    }
+}
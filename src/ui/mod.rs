@@ -1,17 +1,27 @@
-use crate::app::app::{App, CurrentScreen, CurrentlyEditingCredentialField};
+use crate::app::{
+    app::{App, CurrentScreen, CurrentlyEditingCredentialField},
+    otp,
+};
 
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
-    text::Span,
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, LineGauge, Paragraph, Row, Table},
     Frame,
 };
 
 mod footer;
+mod health;
 mod helpers;
+mod otp_import;
 mod popups;
+mod rotation;
+mod search;
+mod text_area;
+mod text_input;
 mod title;
+mod trash;
 
 const TITLE_CHUNK_INDEX: usize = 0;
 const MAIN_CHUNK_INDEX: usize = 1;
@@ -28,7 +38,7 @@ pub fn ui(f: &mut Frame, app: &App) {
         ])
         .split(f.area());
 
-    title::render_title(f, chunks[TITLE_CHUNK_INDEX]);
+    title::render_title(f, app, chunks[TITLE_CHUNK_INDEX]);
 
     match app.current_screen {
         CurrentScreen::Init => {}
@@ -47,45 +57,105 @@ pub fn ui(f: &mut Frame, app: &App) {
         CurrentScreen::SpecificCredentialScreen => {
             render_specific_credentials_screen(f, app, &chunks);
         }
-        CurrentScreen::Exiting => {
-            popups::exit::render_exit_popup(f);
+        CurrentScreen::AttachmentPathScreen => {
+            render_specific_credentials_screen(f, app, &chunks);
+            popups::attachment_path::render_attachment_path_popup(f, app);
+        }
+        CurrentScreen::PasswordGeneratorScreen => {
+            if app.is_generating_master_password {
+                popups::new_password_required::render_new_password_required_popup(f, app);
+            } else {
+                render_specific_credentials_screen(f, app, &chunks);
+            }
+            popups::password_generator::render_password_generator_popup(f, app);
+        }
+        CurrentScreen::OtpImportScreen => {
+            otp_import::render_otp_import_screen(f, app, chunks[MAIN_CHUNK_INDEX]);
+        }
+        CurrentScreen::TrashScreen => {
+            trash::render_trash_screen(f, app, chunks[MAIN_CHUNK_INDEX]);
+        }
+        CurrentScreen::RotationScreen => {
+            rotation::render_rotation_screen(f, app, chunks[MAIN_CHUNK_INDEX]);
+        }
+        CurrentScreen::HealthScreen => {
+            health::render_health_screen(f, app, chunks[MAIN_CHUNK_INDEX]);
+        }
+        CurrentScreen::SearchScreen => {
+            search::render_search_screen(f, app, chunks[MAIN_CHUNK_INDEX]);
         }
     }
 
+    if let Some(confirmation) = &app.confirmation {
+        popups::confirmation::render_confirmation_popup(f, confirmation);
+    }
+
     footer::render_footer(f, app, chunks[FOOTER_CHUNK_INDEX]);
 }
 
 fn render_main_credentials_screen(f: &mut Frame, app: &App, chunks: &[Rect]) {
+    let show_timestamps = app.sort_order.is_by_timestamp();
+
+    // Only the page of the selected website is turned into rows, since a vault may hold tens
+    // of thousands of credentials.
+    let height = chunks[MAIN_CHUNK_INDEX].height.saturating_sub(2).max(1) as usize;
+    let first_index = app.selected_website_index - app.selected_website_index % height;
     let rows: Vec<Row> = app
         .websites
         .iter()
         .enumerate()
-        .map(|(i, encoded_website_name)| {
-            let website_name: String;
-            let style: Style;
-            if i == app.selected_website_index {
+        .skip(first_index)
+        .take(height)
+        .map(|(i, website_name)| {
+            let mut style = if i == app.selected_website_index {
                 // TODO display decoded website
-                website_name = app.websites[i].clone();
-                style = Style::default().bg(Color::LightYellow);
+                Style::default().bg(Color::LightYellow)
             } else {
-                website_name = encoded_website_name.clone();
-                style = Style::default();
+                Style::default()
             };
 
-            Row::new(vec![Cell::from(Span::styled(
-                format!("{: <1000}", website_name),
+            let rotation_status = app.website_rotation_statuses[i];
+            let rotation_hint = if app.is_website_breached(website_name) {
+                style = style.fg(Color::Red);
+                "  (breached password)"
+            } else if rotation_status.is_overdue() {
+                style = style.fg(Color::Red);
+                "  (rotation overdue)"
+            } else if rotation_status.is_due_soon() {
+                style = style.fg(Color::Magenta);
+                "  (rotation due soon)"
+            } else {
+                ""
+            };
+
+            let mut cells = vec![Cell::from(Span::styled(
+                format!("{: <1000}", format!("{}{}", website_name, rotation_hint)),
                 style,
-            ))])
+            ))];
+            if show_timestamps {
+                cells.push(Cell::from(Span::styled(
+                    format!(
+                        "{: <1000}",
+                        helpers::format_timestamp(app.website_timestamps[i])
+                    ),
+                    style,
+                )));
+            }
+
+            Row::new(cells)
         })
         .collect();
 
-    let height = chunks[MAIN_CHUNK_INDEX].height - 2; // TODO: why is the height not equal to number of rows?
-    let visible_rows = helpers::get_visible_rows(app.selected_website_index, rows, height);
-
-    let widths = [Constraint::Length(5), Constraint::Length(5)];
-    let table = Table::new(visible_rows, widths)
-        .block(Block::default().borders(Borders::ALL))
-        .widths(&[Constraint::Percentage(100)]);
+    let widths = if show_timestamps {
+        vec![Constraint::Percentage(70), Constraint::Percentage(30)]
+    } else {
+        vec![Constraint::Percentage(100)]
+    };
+    let table = Table::new(rows, widths).column_spacing(0).block(
+        Block::default()
+            .title(format!("Sorted by {}", app.sort_order.get_name()))
+            .borders(Borders::ALL),
+    );
 
     f.render_widget(table, chunks[MAIN_CHUNK_INDEX]);
 }
@@ -96,15 +166,24 @@ fn render_website_credentials_screen(f: &mut Frame, app: &App, chunks: &[Rect])
         .iter()
         .enumerate()
         .map(|(i, email)| {
-            let style: Style;
+            let mut style: Style;
             if i == app.selected_email_index {
                 style = Style::default().bg(Color::LightYellow);
             } else {
                 style = Style::default();
             };
 
+            let website = app.websites[app.selected_website_index].clone();
+            let breach_hint = match app.get_breach_count(&website, email) {
+                Some(count) => {
+                    style = style.fg(Color::Red);
+                    format!("  (password seen {} times in breaches)", count)
+                }
+                None => String::new(),
+            };
+
             Row::new(vec![Cell::from(Span::styled(
-                format!("{: <1000}", email),
+                format!("{: <1000}", format!("{}{}", email, breach_hint)),
                 style,
             ))])
         })
@@ -133,11 +212,15 @@ fn render_specific_credentials_screen(f: &mut Frame, app: &App, chunks: &[Rect])
             .direction(Direction::Vertical)
             .margin(1)
             .constraints([
+                Constraint::Min(3),
+                Constraint::Min(3),
+                Constraint::Min(3),
                 Constraint::Min(3),
                 Constraint::Min(3),
                 Constraint::Min(3),
                 Constraint::Min(3),
                 Constraint::Max(u16::MAX),
+                Constraint::Length(3),
             ])
             .split(chunks[MAIN_CHUNK_INDEX]);
 
@@ -145,6 +228,26 @@ fn render_specific_credentials_screen(f: &mut Frame, app: &App, chunks: &[Rect])
         let mut email_block = Block::default().title("Email").borders(Borders::ALL);
         let mut username_block = Block::default().title("Username").borders(Borders::ALL);
         let mut password_block = Block::default().title("Password").borders(Borders::ALL);
+        if let Some(strength) = app.get_password_strength() {
+            let mut meter = helpers::format_strength_meter(&strength);
+            if let Some(feedback) = strength.get_feedback() {
+                meter.push_span(Span::raw(format!(" - {}", feedback)));
+            }
+            password_block = password_block.title_bottom(meter);
+        }
+        let mut password_rules_block = Block::default()
+            .title("Password rules (e.g. required: upper; maxlength: 20)")
+            .borders(Borders::ALL);
+        let mut otp_block = Block::default()
+            .title("One-time password (base32 secret or otpauth:// URI)")
+            .borders(Borders::ALL);
+        let mut attachments_block = Block::default().title("Attachments").borders(Borders::ALL);
+        let mut tags_block = Block::default()
+            .title("Tags (comma-separated)")
+            .borders(Borders::ALL);
+        let mut rotation_block = Block::default()
+            .title("Rotation (e.g. 90d or 2025-12-31, empty for the tag default)")
+            .borders(Borders::ALL);
         let mut notes_block = Block::default().title("Notes").borders(Borders::ALL);
 
         let active_style = Style::default().bg(Color::LightYellow).fg(Color::Black);
@@ -160,22 +263,226 @@ fn render_specific_credentials_screen(f: &mut Frame, app: &App, chunks: &[Rect])
             CurrentlyEditingCredentialField::Password => {
                 password_block = password_block.style(active_style)
             }
+            CurrentlyEditingCredentialField::PasswordRules => {
+                password_rules_block = password_rules_block.style(active_style)
+            }
+            CurrentlyEditingCredentialField::Otp => otp_block = otp_block.style(active_style),
+            CurrentlyEditingCredentialField::Attachments => {
+                attachments_block = attachments_block.style(active_style)
+            }
+            CurrentlyEditingCredentialField::Tags => tags_block = tags_block.style(active_style),
+            CurrentlyEditingCredentialField::Rotation => {
+                rotation_block = rotation_block.style(active_style)
+            }
             CurrentlyEditingCredentialField::Notes => notes_block = notes_block.style(active_style),
         };
 
-        let website_text = Paragraph::new(app.website_input.clone()).block(website_block);
-        f.render_widget(website_text, popup_chunks[0]);
+        text_input::render_text_input(
+            f,
+            &app.website_input,
+            website_block,
+            popup_chunks[0],
+            matches!(editing, CurrentlyEditingCredentialField::Website),
+            false,
+        );
+
+        text_input::render_text_input(
+            f,
+            &app.email_input,
+            email_block,
+            popup_chunks[1],
+            matches!(editing, CurrentlyEditingCredentialField::Email),
+            false,
+        );
+
+        text_input::render_text_input(
+            f,
+            &app.username_input,
+            username_block,
+            popup_chunks[2],
+            matches!(editing, CurrentlyEditingCredentialField::Username),
+            false,
+        );
+
+        let password_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(popup_chunks[3]);
+
+        text_input::render_text_input(
+            f,
+            &app.password_input,
+            password_block,
+            password_chunks[0],
+            matches!(editing, CurrentlyEditingCredentialField::Password),
+            !app.is_secret_revealed(),
+        );
 
-        let email_text = Paragraph::new(app.email_input.clone()).block(email_block);
-        f.render_widget(email_text, popup_chunks[1]);
+        text_input::render_text_input(
+            f,
+            &app.password_rules_input,
+            password_rules_block,
+            password_chunks[1],
+            matches!(editing, CurrentlyEditingCredentialField::PasswordRules),
+            false,
+        );
 
-        let username_text = Paragraph::new(app.username_input.clone()).block(username_block);
-        f.render_widget(username_text, popup_chunks[2]);
+        render_otp_field(f, app, otp_block, popup_chunks[4]);
 
-        let password_text = Paragraph::new(app.password_input.clone()).block(password_block);
-        f.render_widget(password_text, popup_chunks[3]);
+        let is_editing_attachments =
+            matches!(editing, CurrentlyEditingCredentialField::Attachments);
+        let attachments_text = Paragraph::new(get_attachments_line(app, is_editing_attachments))
+            .block(attachments_block);
+        f.render_widget(attachments_text, popup_chunks[5]);
 
-        let notes_text = Paragraph::new(app.notes_input.clone()).block(notes_block);
-        f.render_widget(notes_text, popup_chunks[4]);
+        let tags_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(popup_chunks[6]);
+
+        text_input::render_text_input(
+            f,
+            &app.tags_input,
+            tags_block,
+            tags_chunks[0],
+            matches!(editing, CurrentlyEditingCredentialField::Tags),
+            false,
+        );
+
+        text_input::render_text_input(
+            f,
+            &app.rotation_input,
+            rotation_block,
+            tags_chunks[1],
+            matches!(editing, CurrentlyEditingCredentialField::Rotation),
+            false,
+        );
+
+        text_area::render_text_area(
+            f,
+            &app.notes_input,
+            notes_block,
+            popup_chunks[7],
+            matches!(editing, CurrentlyEditingCredentialField::Notes),
+        );
+
+        let details = match &app.loaded_credential {
+            Some(credential) => {
+                let mut details = format!(
+                    "Created: {}   Modified: {}   Password changed: {}   Last used: {}",
+                    helpers::format_timestamp(credential.created_at),
+                    helpers::format_timestamp(credential.modified_at),
+                    helpers::format_timestamp(credential.password_modified_at),
+                    helpers::format_timestamp(credential.last_accessed_at),
+                );
+                if let Some(due_date) = credential.get_rotation_due_date(&app.credentials.settings)
+                {
+                    details.push_str(&format!(
+                        "   Rotation: {}",
+                        helpers::format_due_date(due_date)
+                    ));
+                }
+                if let Some(count) = app.get_breach_count(&credential.website, &credential.email) {
+                    details.push_str(&format!("   Breached: seen {} times", count));
+                }
+                details
+            }
+            None => "New credential".to_string(),
+        };
+        let details_block = Block::default().title("Details").borders(Borders::ALL);
+        let details_text = Paragraph::new(details).block(details_block);
+        f.render_widget(details_text, popup_chunks[8]);
+    }
+}
+
+fn get_attachments_line(app: &App, is_editing: bool) -> Line<'static> {
+    if app.attachments_input.is_empty() {
+        return Line::from("No attachments");
+    }
+
+    let mut spans = Vec::new();
+    for (i, attachment) in app.attachments_input.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" | "));
+        }
+
+        let style = if is_editing && i == app.selected_attachment_index {
+            Style::default().bg(Color::Black).fg(Color::LightYellow)
+        } else {
+            Style::default()
+        };
+        spans.push(Span::styled(
+            format!(
+                "{} ({})",
+                attachment.get_file_name(),
+                helpers::format_size(attachment.get_size())
+            ),
+            style,
+        ));
     }
+
+    Line::from(spans)
+}
+
+fn render_otp_field(f: &mut Frame, app: &App, otp_block: Block, area: Rect) {
+    let otp_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    // The secret is masked like the password, the code it generates is not.
+    text_input::render_text_input(
+        f,
+        &app.otp_input,
+        otp_block,
+        otp_chunks[0],
+        matches!(
+            app.currently_editing_credential_field,
+            Some(CurrentlyEditingCredentialField::Otp)
+        ),
+        !app.is_secret_revealed(),
+    );
+
+    let code_block = Block::default().title("Code").borders(Borders::ALL);
+    let code_area = code_block.inner(otp_chunks[1]);
+    f.render_widget(code_block, otp_chunks[1]);
+
+    let otp_params = match app.get_editing_otp() {
+        Some(otp_params) => otp_params,
+        None => {
+            let hint = if app.otp_input.get_value().trim().is_empty() {
+                ""
+            } else {
+                "invalid secret"
+            };
+            f.render_widget(Paragraph::new(hint), code_area);
+            return;
+        }
+    };
+
+    let unix_time = otp::get_unix_time();
+    let code = otp::generate_code(&otp_params, unix_time);
+
+    if otp_params.is_counter_based() {
+        let code_text = format!("{}  (counter {})", code, otp_params.get_counter());
+        f.render_widget(Paragraph::new(code_text), code_area);
+        return;
+    }
+
+    let code_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(code.len() as u16 + 2),
+            Constraint::Min(1),
+        ])
+        .split(code_area);
+
+    f.render_widget(Paragraph::new(code), code_chunks[0]);
+
+    let seconds_remaining = otp::get_seconds_remaining(&otp_params, unix_time);
+    let countdown = LineGauge::default()
+        .filled_style(Style::default().fg(Color::Green))
+        .ratio(seconds_remaining as f64 / otp_params.get_period() as f64)
+        .label(format!("{:>2}s", seconds_remaining));
+    f.render_widget(countdown, code_chunks[1]);
 }
@@ -0,0 +1,112 @@
+use crate::app::app::App;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+
+use super::{helpers, text_input};
+
+pub fn render_otp_import_screen(f: &mut Frame, app: &App, area: Rect) {
+    if app.otp_import_items.is_empty() {
+        render_otp_import_input(f, app, area);
+    } else {
+        render_otp_import_preview(f, app, area);
+    }
+}
+
+fn render_otp_import_input(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
+
+    let input_block = Block::default()
+        .title("Import one-time passwords")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+    text_input::render_text_input(
+        f,
+        &app.otp_import_input,
+        input_block,
+        chunks[0],
+        true,
+        false,
+    );
+
+    let help_text = Paragraph::new(
+        "Paste an otpauth-migration://offline?data=... URI exported from Google Authenticator, \
+         a single otpauth:// URI, or the path to a text file with one otpauth:// URI per line.",
+    )
+    .block(Block::default().borders(Borders::ALL))
+    .wrap(Wrap { trim: false });
+    f.render_widget(help_text, chunks[1]);
+}
+
+fn render_otp_import_preview(f: &mut Frame, app: &App, area: Rect) {
+    let rows: Vec<Row> = app
+        .otp_import_items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let style = if i == app.selected_otp_import_index {
+                Style::default().bg(Color::LightYellow).fg(Color::Black)
+            } else {
+                Style::default()
+            };
+
+            let checkbox = if item.accepted { "[x]" } else { "[ ]" };
+            let target = if item.is_existing {
+                format!("attach to {} / {}", item.website, item.email)
+            } else {
+                format!("create {} / {}", item.website, item.email)
+            };
+
+            Row::new(vec![
+                Cell::from(Span::styled(format!("{: <1000}", checkbox), style)),
+                Cell::from(Span::styled(
+                    format!("{: <1000}", item.otp.get_issuer()),
+                    style,
+                )),
+                Cell::from(Span::styled(
+                    format!("{: <1000}", item.otp.get_account_name()),
+                    style,
+                )),
+                Cell::from(Span::styled(format!("{: <1000}", target), style)),
+            ])
+        })
+        .collect();
+
+    let height = area.height - 2;
+    let visible_rows = helpers::get_visible_rows(app.selected_otp_import_index, rows, height);
+
+    let accepted = app
+        .otp_import_items
+        .iter()
+        .filter(|item| item.accepted)
+        .count();
+    let table = Table::new(
+        visible_rows,
+        [
+            Constraint::Length(4),
+            Constraint::Percentage(25),
+            Constraint::Percentage(30),
+            Constraint::Percentage(45),
+        ],
+    )
+    .column_spacing(0)
+    .block(
+        Block::default()
+            .title(format!(
+                "{} account(s) found, {} accepted",
+                app.otp_import_items.len(),
+                accepted
+            ))
+            .borders(Borders::ALL),
+    );
+
+    f.render_widget(table, area);
+}
@@ -0,0 +1,54 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear},
+    Frame,
+};
+
+use crate::app::app::{App, AttachmentActionEnum};
+use crate::ui::text_input;
+
+pub fn render_attachment_path_popup(f: &mut Frame, app: &App) {
+    let title = match app.attachment_action {
+        Some(AttachmentActionEnum::Export) => "Export attachment to",
+        _ => "Attach file from",
+    };
+
+    let popup_block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+
+    let area = attachment_path_rect(60, f.area());
+    f.render_widget(Clear, area);
+    text_input::render_text_input(
+        f,
+        &app.attachment_path_input,
+        popup_block,
+        area,
+        true,
+        false,
+    );
+}
+
+fn attachment_path_rect(percent_x: u16, r: Rect) -> Rect {
+    // Cut the given rectangle into three vertical pieces
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(u16::MAX),
+            Constraint::Length(3),
+            Constraint::Fill(u16::MAX),
+        ])
+        .split(r);
+
+    // Then cut the middle vertical piece into three width-wise pieces
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1] // Return the middle chunk
+}
@@ -1,28 +1,45 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
-    text::Text,
-    widgets::{Block, Borders, Paragraph, Wrap},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
-pub fn render_exit_popup(f: &mut Frame) {
+use crate::app::confirmation::Confirmation;
+
+pub fn render_confirmation_popup(f: &mut Frame, confirmation: &Confirmation) {
     let popup_block = Block::default()
-        .title("Unsaved Changes")
-        .borders(Borders::NONE)
-        .style(Style::default().bg(Color::Black));
+        .title(confirmation.title.as_str())
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black).fg(Color::White));
+
+    let mut choices: Vec<Span> = Vec::new();
+    for (i, choice) in confirmation.choices.iter().enumerate() {
+        let key = match choice.key {
+            Some(key) => key.to_ascii_uppercase().to_string(),
+            None => "Esc".to_string(),
+        };
+        let style = if i == confirmation.selected_choice {
+            Style::default().bg(Color::LightYellow).fg(Color::Black)
+        } else {
+            Style::default()
+        };
+        choices.push(Span::raw("  "));
+        choices.push(Span::styled(format!(" ({}) {} ", key, choice.label), style));
+    }
 
-    let exit_text = Text::styled(
-        "Would you like to save your changes? (y/n)",
-        Style::default().fg(Color::White),
-    );
+    let mut lines: Vec<Line> = confirmation.message.lines().map(Line::from).collect();
+    lines.push(Line::default());
+    lines.push(Line::from(choices));
     // the `trim: false` will stop the text from being cut off when over the edge of the block
-    let exit_paragraph = Paragraph::new(exit_text)
+    let confirmation_paragraph = Paragraph::new(Text::from(lines))
         .block(popup_block)
         .wrap(Wrap { trim: false });
 
     let area = centered_rect(60, 25, f.area());
-    f.render_widget(exit_paragraph, area);
+    f.render_widget(Clear, area);
+    f.render_widget(confirmation_paragraph, area);
 }
 
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
@@ -1,11 +1,12 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders},
     Frame,
 };
 
 use crate::app::app::App;
+use crate::ui::text_input;
 
 pub fn render_master_password_required_popup(f: &mut Frame, app: &App) {
     let popup_block = Block::default()
@@ -13,16 +14,15 @@ pub fn render_master_password_required_popup(f: &mut Frame, app: &App) {
         .borders(Borders::ALL)
         .style(Style::default().bg(Color::LightYellow));
 
-    // the `trim: false` will stop the text from being cut off when over the edge of the block
-    let password_str: String = std::iter::repeat('*')
-        .take(app.master_password_input.len())
-        .collect();
-    let password_paragraph = Paragraph::new(password_str)
-        .block(popup_block)
-        .wrap(Wrap { trim: false });
-
     let area = master_password_required_rect(50, f.area());
-    f.render_widget(password_paragraph, area);
+    text_input::render_text_input(
+        f,
+        &app.master_password_input,
+        popup_block,
+        area,
+        true,
+        !app.is_secret_revealed(),
+    );
 }
 
 fn master_password_required_rect(percent_x: u16, r: Rect) -> Rect {
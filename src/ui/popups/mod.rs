@@ -1,3 +1,5 @@
-pub(super) mod exit;
+pub(super) mod attachment_path;
+pub(super) mod confirmation;
 pub(super) mod master_password_required;
 pub(super) mod new_password_required;
+pub(super) mod password_generator;
@@ -1,11 +1,13 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
+    text::Line,
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
 
 use crate::app::app::App;
+use crate::ui::{helpers, text_input};
 
 pub fn render_new_password_required_popup(f: &mut Frame, app: &App) {
     let popup_block = Block::default()
@@ -13,25 +15,49 @@ pub fn render_new_password_required_popup(f: &mut Frame, app: &App) {
         .borders(Borders::ALL)
         .style(Style::default().bg(Color::LightYellow));
 
-    // the `trim: false` will stop the text from being cut off when over the edge of the block
-    let password_str: String = std::iter::repeat('*')
-        .take(app.new_password_input.len())
-        .collect();
-    let password_paragraph = Paragraph::new(password_str)
+    // The first line is left for the password input.
+    let mut lines = vec![Line::from(""), Line::from("")];
+
+    // Feedback on the password while it is typed, so a weak one is not a surprise on Enter.
+    let strength = app.get_new_password_strength();
+    lines.push(helpers::format_strength_meter(&strength));
+    if let Some(warning) = strength.get_warning() {
+        lines.push(Line::styled(
+            warning.to_string(),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    for suggestion in strength.get_suggestions() {
+        lines.push(Line::from(suggestion.clone()));
+    }
+
+    let height = lines.len() as u16 + 2;
+    let area = new_password_required_rect(50, height, f.area());
+    let input_area = Rect {
+        height: 1,
+        ..popup_block.inner(area)
+    };
+    let password_paragraph = Paragraph::new(lines)
         .block(popup_block)
         .wrap(Wrap { trim: false });
-
-    let area = new_password_required_rect(50, f.area());
     f.render_widget(password_paragraph, area);
+    text_input::render_text_input(
+        f,
+        &app.new_password_input,
+        Block::default(),
+        input_area,
+        true,
+        !app.is_secret_revealed(),
+    );
 }
 
-fn new_password_required_rect(percent_x: u16, r: Rect) -> Rect {
+fn new_password_required_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
     // Cut the given rectangle into three vertical pieces
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Fill(u16::MAX),
-            Constraint::Length(3),
+            Constraint::Length(height),
             Constraint::Fill(u16::MAX),
         ])
         .split(r);
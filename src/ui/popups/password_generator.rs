@@ -0,0 +1,87 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::{app::App, password_generator};
+
+pub fn render_password_generator_popup(f: &mut Frame, app: &App) {
+    let settings = &app.credentials.settings.password_generator;
+
+    let popup_block = Block::default()
+        .title("Password generator")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+
+    let options = app.get_generator_options();
+    let area = password_generator_rect(60, options.len() as u16 + 7, f.area());
+    f.render_widget(Clear, area);
+    let inner_area = popup_block.inner(area);
+    f.render_widget(popup_block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Min(1),
+        ])
+        .split(inner_area);
+
+    let password_text = Paragraph::new(app.generated_password.clone())
+        .block(Block::default().title("Password").borders(Borders::ALL));
+    f.render_widget(password_text, chunks[0]);
+
+    let rules = app.get_password_rules();
+    let mut entropy = format!(
+        "Entropy: ~{:.0} bits",
+        password_generator::get_entropy_bits(settings, rules.as_ref())
+    );
+    if rules.is_some() {
+        entropy.push_str(", following the site's password rules");
+    }
+    let entropy_text = Paragraph::new(entropy);
+    f.render_widget(entropy_text, chunks[1]);
+
+    let option_lines: Vec<Line> = options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            let style = if i == app.selected_generator_option {
+                Style::default().bg(Color::Black).fg(Color::LightYellow)
+            } else {
+                Style::default()
+            };
+            Line::from(Span::styled(
+                format!("{: <28}{}", option.get_name(), option.get_value(settings)),
+                style,
+            ))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(option_lines), chunks[2]);
+}
+
+fn password_generator_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
+    // Cut the given rectangle into three vertical pieces
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(u16::MAX),
+            Constraint::Length(height),
+            Constraint::Fill(u16::MAX),
+        ])
+        .split(r);
+
+    // Then cut the middle vertical piece into three width-wise pieces
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1] // Return the middle chunk
+}
@@ -0,0 +1,95 @@
+use crate::app::app::App;
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use super::{helpers, text_input};
+
+pub fn render_rotation_screen(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
+
+    render_rotation_defaults(f, app, chunks[0]);
+
+    let due = app.credentials.get_due_for_rotation();
+    let rows: Vec<Row> = due
+        .iter()
+        .enumerate()
+        .map(|(i, (credential, due_date))| {
+            let style = if i == app.selected_rotation_index {
+                Style::default().bg(Color::LightYellow).fg(Color::Black)
+            } else {
+                Style::default().fg(helpers::get_due_date_color(*due_date))
+            };
+
+            Row::new(vec![
+                Cell::from(Span::styled(
+                    format!("{: <1000}", credential.website),
+                    style,
+                )),
+                Cell::from(Span::styled(format!("{: <1000}", credential.email), style)),
+                Cell::from(Span::styled(
+                    format!("{: <1000}", helpers::format_timestamp(Some(*due_date))),
+                    style,
+                )),
+                Cell::from(Span::styled(
+                    format!("{: <1000}", helpers::format_due_date(*due_date)),
+                    style,
+                )),
+            ])
+        })
+        .collect();
+
+    let height = chunks[1].height - 2;
+    let visible_rows = helpers::get_visible_rows(app.selected_rotation_index, rows, height);
+
+    let table = Table::new(
+        visible_rows,
+        [
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+        ],
+    )
+    .column_spacing(0)
+    .header(Row::new(vec!["Website", "Email", "Due", "Status"]))
+    .block(
+        Block::default()
+            .title(format!("{} credential(s) due for rotation", due.len()))
+            .borders(Borders::ALL),
+    );
+
+    f.render_widget(table, chunks[1]);
+}
+
+fn render_rotation_defaults(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .title("Default rotation by tag (tag=days, ...)")
+        .borders(Borders::ALL);
+
+    let defaults = match &app.rotation_defaults_input {
+        Some(input) => {
+            let block = block.style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+            text_input::render_text_input(f, input, block, area, true, false);
+            return;
+        }
+        None => {
+            let defaults = app.credentials.settings.format_rotation_defaults();
+            if defaults.is_empty() {
+                Paragraph::new("none").block(block)
+            } else {
+                Paragraph::new(defaults).block(block)
+            }
+        }
+    };
+
+    f.render_widget(defaults, area);
+}
@@ -0,0 +1,138 @@
+use crate::app::{
+    app::App,
+    search::{SearchFieldEnum, SearchResult},
+};
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Row, Table},
+    Frame,
+};
+
+use super::text_input;
+
+// The characters kept before the first match of a long tag or line of notes.
+const MATCH_CONTEXT: usize = 10;
+
+pub fn render_search_screen(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(area);
+
+    let input_block = Block::default()
+        .title("Search websites, emails, usernames, tags and notes")
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+    text_input::render_text_input(f, &app.search_input, input_block, chunks[0], true, false);
+
+    // Only the page of the selected result is turned into rows, since a vault may hold tens
+    // of thousands of credentials.
+    let height = chunks[1].height.saturating_sub(2).max(1) as usize;
+    let first_index = app.selected_search_index - app.selected_search_index % height;
+    let rows: Vec<Row> = app
+        .search_results
+        .iter()
+        .enumerate()
+        .skip(first_index)
+        .take(height)
+        .map(|(i, result)| {
+            let style = if i == app.selected_search_index {
+                Style::default().bg(Color::LightYellow).fg(Color::Black)
+            } else {
+                Style::default()
+            };
+            get_result_row(app, result, style)
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Percentage(40),
+        ],
+    )
+    .column_spacing(1)
+    .block(
+        Block::default()
+            .title(format!("Results ({})", app.search_results.len()))
+            .borders(Borders::ALL),
+    );
+    f.render_widget(table, chunks[1]);
+}
+
+fn get_result_row<'a>(app: &'a App, result: &'a SearchResult, style: Style) -> Row<'a> {
+    let credential = &app.credentials.credentials[result.index];
+    let website = match result.field {
+        SearchFieldEnum::Website => get_highlighted_line(&result.text, &result.positions, style),
+        _ => Line::from(Span::styled(credential.website.as_str(), style)),
+    };
+    let email = match result.field {
+        SearchFieldEnum::Email => get_highlighted_line(&result.text, &result.positions, style),
+        _ => Line::from(Span::styled(credential.email.as_str(), style)),
+    };
+    let matched = match result.field {
+        SearchFieldEnum::Website | SearchFieldEnum::Email => Line::default(),
+        field => {
+            // A match far into a long line is brought into view.
+            let skipped = result
+                .positions
+                .first()
+                .map_or(0, |position| position.saturating_sub(MATCH_CONTEXT));
+            let text: String = result.text.chars().skip(skipped).collect();
+            let positions: Vec<usize> = result.positions.iter().map(|p| p - skipped).collect();
+            let mut line = get_highlighted_line(&text, &positions, style);
+            let prefix = if skipped > 0 { "…" } else { "" };
+            line.spans.insert(
+                0,
+                Span::styled(format!("{}: {}", field.get_name(), prefix), style),
+            );
+            line
+        }
+    };
+
+    Row::new(vec![
+        Cell::from(website),
+        Cell::from(email),
+        Cell::from(matched),
+    ])
+    .style(style)
+}
+
+/// Returns the text with the matched characters highlighted.
+fn get_highlighted_line(text: &str, positions: &[usize], style: Style) -> Line<'static> {
+    let highlight_style = style
+        .fg(Color::Red)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans: Vec<Span> = Vec::new();
+    let mut positions = positions.iter().peekable();
+    let mut current = String::new();
+    let mut is_current_matched = false;
+
+    for (i, c) in text.chars().enumerate() {
+        let is_matched = positions.next_if_eq(&&i).is_some();
+        if is_matched != is_current_matched && !current.is_empty() {
+            let span_style = if is_current_matched {
+                highlight_style
+            } else {
+                style
+            };
+            spans.push(Span::styled(std::mem::take(&mut current), span_style));
+        }
+        is_current_matched = is_matched;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        let span_style = if is_current_matched {
+            highlight_style
+        } else {
+            style
+        };
+        spans.push(Span::styled(current, span_style));
+    }
+    Line::from(spans)
+}
@@ -0,0 +1,26 @@
+use ratatui::{layout::Rect, text::Line, widgets::Block, widgets::Paragraph, Frame};
+
+use crate::app::text_area::TextArea;
+
+/// Renders the text area in the block, with long lines wrapped. The focused text area shows
+/// the terminal cursor.
+pub fn render_text_area(
+    f: &mut Frame,
+    text_area: &TextArea,
+    block: Block,
+    area: Rect,
+    is_focused: bool,
+) {
+    let inner_area = block.inner(area);
+    let (rows, cursor_column, cursor_row) =
+        text_area.get_view(inner_area.width as usize, inner_area.height as usize);
+    let lines: Vec<Line> = rows.into_iter().map(Line::from).collect();
+    f.render_widget(Paragraph::new(lines).block(block), area);
+
+    if is_focused && inner_area.width > 0 && inner_area.height > 0 {
+        f.set_cursor_position((
+            inner_area.x + cursor_column as u16,
+            inner_area.y + cursor_row as u16,
+        ));
+    }
+}
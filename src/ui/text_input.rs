@@ -0,0 +1,21 @@
+use ratatui::{layout::Rect, widgets::Block, widgets::Paragraph, Frame};
+
+use crate::app::text_input::TextInput;
+
+/// Renders the input in the block. The focused input shows the terminal cursor.
+pub fn render_text_input(
+    f: &mut Frame,
+    input: &TextInput,
+    block: Block,
+    area: Rect,
+    is_focused: bool,
+    is_masked: bool,
+) {
+    let inner_area = block.inner(area);
+    let (view, cursor_column) = input.get_view(inner_area.width as usize, is_masked);
+    f.render_widget(Paragraph::new(view).block(block), area);
+
+    if is_focused && inner_area.width > 0 && inner_area.height > 0 {
+        f.set_cursor_position((inner_area.x + cursor_column as u16, inner_area.y));
+    }
+}
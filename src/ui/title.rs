@@ -1,18 +1,26 @@
 use ratatui::{
     layout::Rect,
-    style::Style,
-    text::Text,
+    style::{Color, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
-pub fn render_title(f: &mut Frame, area: Rect) {
+use crate::app::app::App;
+
+pub fn render_title(f: &mut Frame, app: &App, area: Rect) {
     let title_block = Block::default()
         .borders(Borders::ALL)
         .style(Style::default());
 
-    let title =
-        Paragraph::new(Text::styled("Password Manager", Style::default())).block(title_block);
+    let mut spans = vec![Span::styled("Password Manager", Style::default())];
+    if app.unsaved_changes {
+        spans.push(Span::styled(
+            "  [modified, (^S) to save]",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    let title = Paragraph::new(Line::from(spans)).block(title_block);
 
     f.render_widget(title, area);
 }
@@ -0,0 +1,81 @@
+use crate::app::app::App;
+
+use chrono::{Duration, Utc};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Cell, Row, Table},
+    Frame,
+};
+
+use super::helpers;
+
+pub fn render_trash_screen(f: &mut Frame, app: &App, area: Rect) {
+    let retention_days = app.credentials.settings.trash_retention_days;
+
+    let rows: Vec<Row> = app
+        .credentials
+        .trash
+        .iter()
+        .enumerate()
+        .map(|(i, trashed)| {
+            let style = if i == app.selected_trash_index {
+                Style::default().bg(Color::LightYellow).fg(Color::Black)
+            } else {
+                Style::default()
+            };
+
+            let purge_date = if retention_days == 0 {
+                "never".to_string()
+            } else {
+                let purge_at = trashed.deleted_at + Duration::days(retention_days as i64);
+                let days_left = (purge_at - Utc::now()).num_days().max(0);
+                format!("in {} day(s)", days_left)
+            };
+
+            Row::new(vec![
+                Cell::from(Span::styled(
+                    format!("{: <1000}", trashed.credential.website),
+                    style,
+                )),
+                Cell::from(Span::styled(
+                    format!("{: <1000}", trashed.credential.email),
+                    style,
+                )),
+                Cell::from(Span::styled(
+                    format!(
+                        "{: <1000}",
+                        helpers::format_timestamp(Some(trashed.deleted_at))
+                    ),
+                    style,
+                )),
+                Cell::from(Span::styled(format!("{: <1000}", purge_date), style)),
+            ])
+        })
+        .collect();
+
+    let height = area.height - 2;
+    let visible_rows = helpers::get_visible_rows(app.selected_trash_index, rows, height);
+
+    let title = if retention_days == 0 {
+        "Trash (kept until purged)".to_string()
+    } else {
+        format!("Trash (purged after {} days)", retention_days)
+    };
+
+    let table = Table::new(
+        visible_rows,
+        [
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Percentage(20),
+            Constraint::Percentage(20),
+        ],
+    )
+    .column_spacing(0)
+    .header(Row::new(vec!["Website", "Email", "Deleted", "Purged"]))
+    .block(Block::default().title(title).borders(Borders::ALL));
+
+    f.render_widget(table, area);
+}